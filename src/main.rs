@@ -1,10 +1,97 @@
 use ::tracing::{error, info};
-use dotenv;
+use clap::{Parser, Subcommand};
 
 pub mod tracing;
 pub mod xrpl_http;
 
-use xrpl_http::{ClientService, TransactionService};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use xrpl_http::{
+    BatchOptions, DerivationPath, Error, FeePolicy, FeeStrategy, KeyType, Keystore, PayoutStatus,
+    RemoteSigner, RippleSigner, SecretString, Token, TransactionService, batch, fee,
+    remote_signer,
+};
+
+#[derive(Parser)]
+#[command(name = "shogun-xrp", about = "XRP Ledger showcase")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a new account and print it as JSON
+    GenerateWallet {
+        /// Signing algorithm of the new key pair
+        #[arg(long, value_enum, default_value_t = KeyType::Secp256k1)]
+        key_type: KeyType,
+        /// Encode the X-address for testnet instead of mainnet
+        #[arg(long)]
+        testnet: bool,
+    },
+    /// Serve a keystore account as a signing daemon on a Unix socket
+    ServeSigner {
+        /// Keystore account to sign for
+        #[arg(long)]
+        account: String,
+        /// Path of the Unix socket to listen on
+        #[arg(long)]
+        socket: PathBuf,
+    },
+    /// Make a keystore account the regular key of another one
+    RotateRegularKey {
+        /// Keystore account whose regular key is rotated, signed with its master key
+        #[arg(long)]
+        account: String,
+        /// Keystore account holding the new regular key
+        #[arg(long)]
+        new_key: String,
+        /// Disable the master key once the new regular key has signed
+        #[arg(long)]
+        disable_master: bool,
+    },
+    /// Pay every row of a CSV or JSON payout file from a keystore account
+    PayBatch {
+        /// Keystore account to pay from
+        #[arg(long)]
+        account: String,
+        /// Payout file with destination, currency, issuer, amount, destination_tag and memo
+        #[arg(long)]
+        file: PathBuf,
+        /// Results file (JSON Lines); run again with the same file to resume the batch
+        #[arg(long)]
+        results: PathBuf,
+        /// Payments submitted but not final yet
+        #[arg(long, default_value_t = 10)]
+        max_in_flight: usize,
+        /// Milliseconds between two submissions
+        #[arg(long, default_value_t = 250)]
+        interval_ms: u64,
+    },
+    /// Manage the encrypted keystore at KEYSTORE_PATH
+    Keystore {
+        #[command(subcommand)]
+        command: KeystoreCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeystoreCommand {
+    /// List the accounts in the keystore
+    List,
+    /// Add an account, reading its family seed from stdin
+    Add { name: String },
+    /// Remove an account
+    Remove { name: String },
+}
+
+const DEFAULT_KEYSTORE_PATH: &str = "keystore.json";
+const MIDDLE_MAN_ACCOUNT: &str = "middle-man";
+const SOLVER_ACCOUNT: &str = "solver";
 
 #[tokio::main]
 async fn main() {
@@ -15,31 +102,246 @@ async fn main() {
     
     dotenv::dotenv().ok();
 
-    // The seed key starts with "s".
-    let seed_middle_man = &std::env::var("SEED_MIDDLE_MAN").expect("SEED not set on .env");
-    let seed_solver = &std::env::var("SEED_SOLVER").expect("SEED not set on .env");
-    
-    info!("Middle man seed: {}", seed_middle_man);
-    info!("Solver seed: {}", seed_solver);
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Command::GenerateWallet { key_type, testnet }) => generate_wallet(key_type, testnet),
+        Some(Command::ServeSigner { account, socket }) => serve_signer(&account, &socket).await,
+        Some(Command::RotateRegularKey {
+            account,
+            new_key,
+            disable_master,
+        }) => rotate_regular_key(&account, &new_key, disable_master).await,
+        Some(Command::PayBatch {
+            account,
+            file,
+            results,
+            max_in_flight,
+            interval_ms,
+        }) => {
+            let options = BatchOptions {
+                max_in_flight,
+                submit_interval: Duration::from_millis(interval_ms),
+            };
+            pay_batch(&account, &file, &results, &options).await
+        }
+        Some(Command::Keystore { command }) => manage_keystore(command),
+        None => run().await,
+    }
+}
+
+fn generate_wallet(key_type: KeyType, testnet: bool) {
+    let mut wallet = match RippleSigner::generate(key_type) {
+        Ok(wallet) => wallet,
+        Err(e) => {
+            error!("Failed to generate wallet: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if testnet {
+        wallet.x_address =
+            xrpl_http::address::encode_x_address(&wallet.classic_address, None, true).unwrap();
+    }
+
+    println!("{}", serde_json::to_string_pretty(&wallet).unwrap());
+}
+
+fn open_keystore(create: bool) -> Keystore {
+    let path = std::env::var("KEYSTORE_PATH").unwrap_or_else(|_| DEFAULT_KEYSTORE_PATH.to_string());
+    let password = SecretString::from_env("KEYSTORE_PASSWORD").expect("KEYSTORE_PASSWORD not set on .env");
+
+    let keystore = if create {
+        Keystore::open_or_create(&path, &password)
+    } else {
+        Keystore::open(&path, &password)
+    };
+
+    match keystore {
+        Ok(keystore) => keystore,
+        Err(e) => {
+            error!("Failed to open keystore: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn manage_keystore(command: KeystoreCommand) {
+    let mut keystore = open_keystore(matches!(command, KeystoreCommand::Add { .. }));
+
+    let result = match command {
+        KeystoreCommand::List => Ok(serde_json::to_string_pretty(&keystore.list()).unwrap()),
+        KeystoreCommand::Add { name } => {
+            let mut line = SecretString::new(String::new());
+            std::io::stdin()
+                .read_line(line.expose_secret_mut())
+                .expect("Failed to read seed from stdin");
+            let seed = SecretString::new(line.expose_secret().trim().to_string());
+
+            keystore
+                .add(&name, &seed)
+                .map(|entry| serde_json::to_string_pretty(&entry).unwrap())
+        }
+        KeystoreCommand::Remove { name } => keystore
+            .remove(&name)
+            .map(|_| format!("Removed account {name}")),
+    };
+
+    match result {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            error!("Keystore operation failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn serve_signer(account: &str, socket: &Path) {
+    let signer = open_keystore(false).load(account).unwrap();
 
-    let solver_service = TransactionService::from_seed(seed_solver).unwrap();
+    if let Err(e) = remote_signer::serve(signer, socket).await {
+        error!("Signing daemon stopped: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn rotate_regular_key(account: &str, new_key: &str, disable_master: bool) {
+    let keystore = open_keystore(false);
+    let service = TransactionService::new(keystore.load(account).unwrap());
+    let new_key = keystore
+        .load(new_key)
+        .and_then(|signer| signer.for_account(service.address()))
+        .unwrap();
+
+    match service.rotate_regular_key(&new_key, disable_master).await {
+        Ok(rotation) => info!(
+            "Regular key of {} is now {} (master key disabled: {})",
+            service.address(),
+            rotation.regular_key,
+            rotation.disable_master.is_some()
+        ),
+        Err(e) => {
+            error!("Regular key rotation failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn pay_batch(account: &str, file: &Path, results: &Path, options: &BatchOptions) {
+    let payouts = match batch::load_payouts(file) {
+        Ok(payouts) => payouts,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let service = TransactionService::new(open_keystore(false).load(account).unwrap())
+        .with_fee_strategy(fee_strategy_or_exit());
+
+    match service.pay_batch(&payouts, results, options).await {
+        Ok(records) => {
+            let count = |status| records.iter().filter(|record| record.status == status).count();
+            info!(
+                "Batch of {} payouts: {} succeeded, {} failed, {} expired, {} pending; see {}",
+                payouts.len(),
+                count(PayoutStatus::Succeeded),
+                count(PayoutStatus::Failed),
+                count(PayoutStatus::Expired),
+                count(PayoutStatus::Pending),
+                results.display()
+            );
+        }
+        Err(e) => {
+            error!("Batch payout failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Fee strategy from FEE_POLICY, MAX_FEE_DROPS and MAX_FEES_PER_HOUR_DROPS, falling back to
+/// the defaults for unset variables
+fn fee_strategy_from_env() -> Result<FeeStrategy, Error> {
+    let policy = match std::env::var("FEE_POLICY") {
+        Ok(policy) => policy.parse::<FeePolicy>()?,
+        Err(_) => FeePolicy::default(),
+    };
+    let max_fee = drops_from_env("MAX_FEE_DROPS")?.unwrap_or(fee::DEFAULT_MAX_FEE_PER_TRANSACTION);
+    let max_fees_per_hour =
+        drops_from_env("MAX_FEES_PER_HOUR_DROPS")?.unwrap_or(fee::DEFAULT_MAX_FEES_PER_HOUR);
+
+    Ok(FeeStrategy::new(policy, max_fee, max_fees_per_hour))
+}
+
+fn drops_from_env(name: &str) -> Result<Option<u64>, Error> {
+    std::env::var(name)
+        .ok()
+        .map(|drops| {
+            drops
+                .parse::<u64>()
+                .map_err(|e| Error::validation(format!("{name} must be a number: {e}")))
+        })
+        .transpose()
+}
+
+/// [`fee_strategy_from_env`], exiting on invalid configuration
+fn fee_strategy_or_exit() -> Arc<FeeStrategy> {
+    match fee_strategy_from_env() {
+        Ok(fee_strategy) => Arc::new(fee_strategy),
+        Err(e) => {
+            error!("Invalid fee configuration: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run() {
+    let keystore = open_keystore(false);
+    // One hourly fee budget for every account of this process
+    let fee_strategy = fee_strategy_or_exit();
+
+    // The middle man key can be kept in a separate `serve-signer` process.
+    let mm_service = match std::env::var("MIDDLE_MAN_SIGNER_SOCKET") {
+        Ok(socket) => TransactionService::new(RemoteSigner::connect(socket).await.unwrap()),
+        Err(_) => TransactionService::new(keystore.load(MIDDLE_MAN_ACCOUNT).unwrap()),
+    }
+    .with_fee_strategy(fee_strategy.clone());
+
+    // Solvers can instead be derived from a BIP39 mnemonic at m/44'/144'/0'/0/SOLVER_INDEX.
+    let solver_service = match SecretString::from_env("MNEMONIC") {
+        Ok(mnemonic) => {
+            let passphrase = SecretString::from_env("MNEMONIC_PASSPHRASE").ok();
+            let index = std::env::var("SOLVER_INDEX")
+                .ok()
+                .map(|index| index.parse::<u32>().expect("SOLVER_INDEX must be a number"))
+                .unwrap_or(0);
+            let path = DerivationPath::new(0, index);
+            info!("Deriving solver from mnemonic at {}", path);
+
+            TransactionService::from_mnemonic(&mnemonic, passphrase.as_ref(), path).unwrap()
+        }
+        Err(_) => TransactionService::new(keystore.load(SOLVER_ACCOUNT).unwrap()),
+    }
+    .with_fee_strategy(fee_strategy);
     
     let solver_address = solver_service.address();
     info!("Solver address: {}", solver_address);
-    
-    let mm_service = TransactionService::from_seed(seed_middle_man).unwrap();
     info!("Middle man address: {}", mm_service.address());
     
-    let usdc_address = "rGm7WCVp9gb4jZHWTEtGUr4dd74z2XuWhE";
+    // let usdc_address = "rGm7WCVp9gb4jZHWTEtGUr4dd74z2XuWhE";
     let ripple_usd_address = "rMxCKbEDwqr76QuheSUMdEGf4B9xJ8m5De"; // USD
 
-    let solver_trustline = solver_service.create_trust_line(ripple_usd_address, None).await.unwrap();
+    let ripple_usd = Token::issued("USD", ripple_usd_address).unwrap();
+    let solver_trustline = solver_service.create_trust_line(&ripple_usd, None).await.unwrap();
     info!("Solver trustline: {:?}", solver_trustline);
     // let amount = "0.1";
     // 
-    // let payment_bytes = mm_service.send_token_as_bytes(usdc_address, amount, solver_address).await.unwrap();
+    // // Ticketed, so several pre-signed payments can be submitted in any order
+    // let usdc = Token::resolve(mm_service.client_service(), usdc_address).await.unwrap();
+    // let payment_bytes = mm_service.send_token_as_bytes_with_ticket(&usdc, amount, solver_address, &PaymentOptions::default()).await.unwrap();
     // 
-    // let submit_by_solver = solver_service.send_transaction_from_bytes(payment_bytes).await.unwrap();
+    // let expected = ExpectedPayment::for_token(solver_address.to_string(), &usdc, amount.parse().unwrap());
+    // let submit_by_solver = solver_service.send_payment_from_bytes(payment_bytes, &expected).await.unwrap();
     // 
     // info!("Submit by solver: {:?}", submit_by_solver);
     
@@ -48,7 +350,6 @@ async fn main() {
     // let usdc_address = "rGm7WCVp9gb4jZHWTEtGUr4dd74z2XuWhE";
     // let army_address = "rGG3wQ4kUzd7Jnmk1n5NWPZjjut62kCBfC";
     // let token_find_address = "r9Xzi4KsSF1Xtr8WHyBmUcvfP9FzTyG5wp";
-    // let xrp_address = "XRP";
 
     // let tx_hash = "C4283F49564A12BFC52933FA4B94C4E255E2D54C354264770A6C397FAF6E45A3";
 
@@ -57,8 +358,8 @@ async fn main() {
     // info!("Details: {:?}", details);
 
     // let swap_request = SwapRequest::new(
-    //     token_find_address.to_string(),
-    //     xrp_address.to_string(),
+    //     Token::resolve(&client_service, token_find_address).await.unwrap(),
+    //     Token::Xrp,
     //     "46.27819".to_string(),
     //     "0.8".to_string(),
     // );
@@ -70,7 +371,7 @@ async fn main() {
 
     // info!("Execung swap request: {:?}", swap_request);
 
-    // match transaction_service.swap(swap_request).await {
+    // match transaction_service.swap(swap_request, &PaymentOptions::default()).await {
     //     Ok(response) => {
     //     }
     //     Err(e) => {
@@ -1,8 +1,18 @@
-use tracing::{Level, level_filters::LevelFilter};
+use std::fmt::Debug;
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+};
 use tracing_subscriber::{
-    filter::Targets, fmt::writer::MakeWriterExt, layer::SubscriberExt, util::SubscriberInitExt,
+    filter::Targets,
+    fmt::writer::MakeWriterExt,
+    layer::{Context, Layer, SubscriberExt},
+    util::SubscriberInitExt,
 };
 
+use crate::xrpl_http::secret::REDACTED_SECRET;
+
 pub fn init() -> Result<(), String> {
     let log_level = LevelFilter::INFO;
 
@@ -16,6 +26,7 @@ pub fn init() -> Result<(), String> {
 
     let registry = tracing_subscriber::Registry::default()
         .with(target)
+        .with(SecretGuard)
         .with(stdout_log_layer);
 
     // Initialize global subscriber
@@ -23,3 +34,36 @@ pub fn init() -> Result<(), String> {
 
     Ok(())
 }
+
+/// Refuses events with a `SecretString` in their message or fields, so a secret
+/// passed to a log macro never reaches any output, not even redacted
+struct SecretGuard;
+
+impl<S: Subscriber> Layer<S> for SecretGuard {
+    fn event_enabled(&self, event: &Event<'_>, _ctx: Context<'_, S>) -> bool {
+        let mut visitor = SecretVisitor { found: false };
+        event.record(&mut visitor);
+
+        if visitor.found {
+            eprintln!(
+                "Refused to log an event containing a secret at {}:{}",
+                event.metadata().file().unwrap_or("unknown"),
+                event.metadata().line().unwrap_or(0)
+            );
+        }
+
+        !visitor.found
+    }
+}
+
+struct SecretVisitor {
+    found: bool,
+}
+
+impl Visit for SecretVisitor {
+    fn record_debug(&mut self, _field: &Field, value: &dyn Debug) {
+        if format!("{value:?}").contains(REDACTED_SECRET) {
+            self.found = true;
+        }
+    }
+}
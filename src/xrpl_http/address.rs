@@ -0,0 +1,199 @@
+use xrpl_types::AccountId;
+
+use super::error::Error;
+
+/// X-address prefix for mainnet <https://xrpaddress.info>
+const X_ADDRESS_PREFIX_MAINNET: [u8; 2] = [0x05, 0x44];
+/// X-address prefix for testnet and devnet
+const X_ADDRESS_PREFIX_TESTNET: [u8; 2] = [0x04, 0x93];
+
+/// Encode a classic address and optional destination tag as an X-address
+pub fn encode_x_address(
+    classic_address: &str,
+    tag: Option<u32>,
+    test_network: bool,
+) -> Result<String, Error> {
+    let account_id = AccountId::from_address(classic_address)
+        .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+
+    let prefix = if test_network {
+        X_ADDRESS_PREFIX_TESTNET
+    } else {
+        X_ADDRESS_PREFIX_MAINNET
+    };
+
+    let mut payload = Vec::with_capacity(31);
+    payload.extend_from_slice(&prefix);
+    payload.extend_from_slice(&account_id.0);
+    payload.push(u8::from(tag.is_some()));
+    payload.extend_from_slice(&tag.unwrap_or(0).to_le_bytes());
+    payload.extend_from_slice(&[0u8; 4]);
+
+    Ok(bs58::encode(payload)
+        .with_alphabet(bs58::Alphabet::RIPPLE)
+        .with_check()
+        .into_string())
+}
+
+/// A classic address with the destination tag an X-address may carry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedAddress {
+    pub classic_address: String,
+    pub tag: Option<u32>,
+    /// Whether an X-address was encoded for testnet; `false` for classic addresses
+    pub test_network: bool,
+}
+
+/// Whether `address` looks like an X-address rather than a classic address
+pub fn is_x_address(address: &str) -> bool {
+    address.len() == 47 && (address.starts_with('X') || address.starts_with('T'))
+}
+
+/// Decode an X-address into its classic address and destination tag
+pub fn decode_x_address(x_address: &str) -> Result<DecodedAddress, Error> {
+    let payload = bs58::decode(x_address)
+        .with_alphabet(bs58::Alphabet::RIPPLE)
+        .with_check(None)
+        .into_vec()
+        .map_err(|e| Error::validation(format!("Invalid X-address {x_address}: {e}")))?;
+
+    if payload.len() != 31 {
+        return Err(Error::validation(format!("Invalid X-address length: {x_address}")));
+    }
+
+    let test_network = match [payload[0], payload[1]] {
+        X_ADDRESS_PREFIX_MAINNET => false,
+        X_ADDRESS_PREFIX_TESTNET => true,
+        _ => return Err(Error::validation(format!("Invalid X-address prefix: {x_address}"))),
+    };
+
+    let mut account_id = [0u8; 20];
+    account_id.copy_from_slice(&payload[2..22]);
+
+    let tag = u32::from_le_bytes(payload[23..27].try_into().unwrap());
+    if payload[27..].iter().any(|b| *b != 0) {
+        return Err(Error::validation(format!(
+            "64-bit destination tags are not supported: {x_address}"
+        )));
+    }
+
+    let tag = match payload[22] {
+        0 if tag == 0 => None,
+        1 => Some(tag),
+        _ => return Err(Error::validation(format!("Invalid X-address tag flag: {x_address}"))),
+    };
+
+    Ok(DecodedAddress {
+        classic_address: AccountId(account_id).to_address(),
+        tag,
+        test_network,
+    })
+}
+
+/// Decode a classic address or an X-address
+pub fn decode_address(address: &str) -> Result<DecodedAddress, Error> {
+    if is_x_address(address) {
+        return decode_x_address(address);
+    }
+
+    AccountId::from_address(address)
+        .map_err(|e| Error::validation(format!("Invalid account address {address}: {e}")))?;
+
+    Ok(DecodedAddress {
+        classic_address: address.to_string(),
+        tag: None,
+        test_network: false,
+    })
+}
+
+/// Classic form of a classic address or X-address, for lookups where a tag has no meaning
+pub fn to_classic_address(address: &str) -> Result<String, Error> {
+    decode_address(address).map(|decoded| decoded.classic_address)
+}
+
+/// Account id of a classic address or X-address, for lookups where a tag has no meaning
+pub fn decode_account_id(address: &str) -> Result<AccountId, Error> {
+    let classic_address = to_classic_address(address)?;
+    AccountId::from_address(&classic_address)
+        .map_err(|e| Error::validation(format!("Invalid account address: {e}")))
+}
+
+/// Resolve a payment destination given as a classic address or X-address, together with
+/// an explicit destination tag. The tags must agree when both are present.
+pub fn resolve_destination(
+    address: &str,
+    destination_tag: Option<u32>,
+) -> Result<(AccountId, Option<u32>), Error> {
+    let decoded = decode_address(address)?;
+
+    let tag = match (decoded.tag, destination_tag) {
+        (Some(address_tag), Some(tag)) if address_tag != tag => {
+            return Err(Error::validation(format!(
+                "Destination tag {tag} conflicts with tag {address_tag} of X-address {address}"
+            )));
+        }
+        (address_tag, tag) => address_tag.or(tag),
+    };
+
+    let account_id = AccountId::from_address(&decoded.classic_address)
+        .map_err(|e| Error::validation(format!("Invalid destination address: {e}")))?;
+
+    Ok((account_id, tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASSIC_ADDRESS: &str = "r9cZA1mLK5R5Am25ArfXFmqgNwjZgnfk59";
+
+    /// Vectors from the xrpl-address-codec test suite: (tag, test network, X-address)
+    const X_ADDRESSES: &[(Option<u32>, bool, &str)] = &[
+        (None, false, "X7AcgcsBL6XDcUb289X4mJ8djcdyKaB5hJDWMArnXr61cqZ"),
+        (Some(1), false, "X7AcgcsBL6XDcUb289X4mJ8djcdyKaGZMhc9YTE92ehJ2Fu"),
+        (Some(14), false, "X7AcgcsBL6XDcUb289X4mJ8djcdyKaGo2K5VpXpmCqbV2gS"),
+        (Some(11747), false, "X7AcgcsBL6XDcUb289X4mJ8djcdyKaLFuhLRuNXPrDeJd9A"),
+        (None, true, "T719a5UwUCnEs54UsxG9CJYYDhwmFCqkr7wxCcNcfZ6p5GZ"),
+    ];
+
+    #[test]
+    fn encodes_known_x_addresses() {
+        for (tag, test_network, x_address) in X_ADDRESSES {
+            assert_eq!(
+                encode_x_address(CLASSIC_ADDRESS, *tag, *test_network).unwrap(),
+                *x_address
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_known_x_addresses() {
+        for (tag, test_network, x_address) in X_ADDRESSES {
+            assert_eq!(
+                decode_x_address(x_address).unwrap(),
+                DecodedAddress {
+                    classic_address: CLASSIC_ADDRESS.to_string(),
+                    tag: *tag,
+                    test_network: *test_network,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn xrpaddress_info_example_round_trips() {
+        let classic_address = "rPEPPER7kfTD9w2To4CQk6UCfuHM9c6GDY";
+        let x_address = "XV5sbjUmgPpvXv4ixFWZ5ptAYZ6PD2gYsjNFQLKYW33DzBm";
+
+        assert_eq!(encode_x_address(classic_address, None, false).unwrap(), x_address);
+        assert_eq!(to_classic_address(x_address).unwrap(), classic_address);
+    }
+
+    #[test]
+    fn rejects_conflicting_destination_tags() {
+        let x_address = X_ADDRESSES[1].2;
+        assert!(resolve_destination(x_address, Some(2)).is_err());
+        assert_eq!(resolve_destination(x_address, Some(1)).unwrap().1, Some(1));
+        assert_eq!(resolve_destination(CLASSIC_ADDRESS, Some(2)).unwrap().1, Some(2));
+    }
+}
@@ -0,0 +1,253 @@
+//! Exact decimal amounts, converted without loss between human readable strings, XRP
+//! drops and the mantissa and exponent of issued values
+//! <https://xrpl.org/currency-formats.html>.
+
+use bigdecimal::{BigDecimal, Signed, ToPrimitive, Zero, num_bigint::BigInt};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::{fmt, ops, str::FromStr};
+use xrpl_types::{DropsAmount, IssuedValue};
+
+use super::error::Error;
+
+/// Drops per XRP
+pub const DROPS_PER_XRP: u64 = 1_000_000;
+
+/// All XRP that will ever exist, in drops
+pub const MAX_DROPS: u64 = 100_000_000_000 * DROPS_PER_XRP;
+
+/// Significant digits of an issued value
+pub const ISSUED_PRECISION: u64 = 15;
+
+/// Exponent range of an issued value with a 16 digit mantissa
+pub const MIN_ISSUED_EXPONENT: i64 = -96;
+pub const MAX_ISSUED_EXPONENT: i64 = 80;
+
+/// Digits of the normalized mantissa of an issued value
+const MANTISSA_DIGITS: u64 = 16;
+
+/// An exact decimal amount, in XRP (not drops) or units of an issued token.
+///
+/// Parsing accepts any decimal string; whether the value fits the ledger is checked when
+/// converting with [`Self::to_drops`] or [`Self::to_issued_value`], which never round.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct Decimal(BigDecimal);
+
+impl Decimal {
+    pub fn new(value: BigDecimal) -> Self {
+        Self(value.normalized())
+    }
+
+    pub fn zero() -> Self {
+        Self(BigDecimal::zero())
+    }
+
+    /// The XRP value of `drops`
+    pub fn from_drops(drops: u64) -> Self {
+        Self::new(BigDecimal::new(BigInt::from(drops), 6))
+    }
+
+    /// The value of an issued amount as the ledger stores it
+    pub fn from_issued_value(value: &IssuedValue) -> Self {
+        Self::new(BigDecimal::new(
+            BigInt::from(value.mantissa()),
+            -i64::from(value.exponent()),
+        ))
+    }
+
+    /// Parse a value in drops, e.g. the `Fee` or `Balance` of a transaction
+    pub fn parse_drops(drops: &str) -> Result<Self, Error> {
+        drops
+            .parse::<u64>()
+            .map(Self::from_drops)
+            .map_err(|e| Error::validation(format!("Invalid drops amount {drops}: {e}")))
+    }
+
+    pub fn as_big_decimal(&self) -> &BigDecimal {
+        &self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.0.is_positive()
+    }
+
+    /// This XRP value in drops; fails for fractions of a drop, negative values and more
+    /// XRP than exists
+    pub fn to_drops(&self) -> Result<u64, Error> {
+        let drops = &self.0 * BigDecimal::from(DROPS_PER_XRP);
+        if !drops.is_integer() {
+            return Err(Error::validation(format!(
+                "XRP amount {self} is not a whole number of drops"
+            )));
+        }
+
+        drops
+            .to_u64()
+            .filter(|drops| *drops <= MAX_DROPS)
+            .ok_or_else(|| Error::validation(format!("XRP amount {self} is out of range")))
+    }
+
+    pub fn to_drops_amount(&self) -> Result<DropsAmount, Error> {
+        DropsAmount::from_drops(self.to_drops()?)
+            .map_err(|e| Error::validation(format!("Invalid drops amount: {e}")))
+    }
+
+    /// This value with the normalized 16 digit mantissa and exponent the ledger stores;
+    /// fails for more than 15 significant digits or an exponent out of range
+    pub fn to_issued_value(&self) -> Result<IssuedValue, Error> {
+        if self.is_zero() {
+            return Ok(IssuedValue::zero());
+        }
+
+        let (digits, scale) = self.0.normalized().into_bigint_and_scale();
+        let significant_digits = digits.magnitude().to_string().len() as u64;
+        if significant_digits > ISSUED_PRECISION {
+            return Err(Error::validation(format!(
+                "Amount {self} has more than {ISSUED_PRECISION} significant digits"
+            )));
+        }
+
+        let shift = MANTISSA_DIGITS - significant_digits;
+        let mantissa = (digits * BigInt::from(10u64.pow(shift as u32)))
+            .to_i64()
+            .ok_or_else(|| Error::validation(format!("Amount {self} is out of range")))?;
+        let exponent = -scale - shift as i64;
+        if !(MIN_ISSUED_EXPONENT..=MAX_ISSUED_EXPONENT).contains(&exponent) {
+            return Err(Error::validation(format!("Amount {self} is out of range")));
+        }
+
+        IssuedValue::from_mantissa_exponent(mantissa, exponent as i8)
+            .map_err(|e| Error::validation(format!("Failed to create issued value: {e}")))
+    }
+}
+
+/// The value of an amount from an API response, in XRP rather than drops for XRP
+pub fn value_of(amount: &xrpl_http_client::Amount) -> Result<Decimal, Error> {
+    match amount {
+        xrpl_http_client::Amount::Drops(drops) => Decimal::parse_drops(drops),
+        xrpl_http_client::Amount::Issued(issued) => issued.value.parse(),
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BigDecimal::from_str(s.trim())
+            .map(Self::new)
+            .map_err(|e| Error::validation(format!("Invalid amount {s}: {e}")))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_plain_string())
+    }
+}
+
+impl From<BigDecimal> for Decimal {
+    fn from(value: BigDecimal) -> Self {
+        Self::new(value)
+    }
+}
+
+impl ops::Sub for Decimal {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.0 - rhs.0)
+    }
+}
+
+impl ops::Neg for Decimal {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+/// An amount of XRP, given in XRP or in drops
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XrpAmount {
+    Xrp(Decimal),
+    Drops(u64),
+}
+
+impl XrpAmount {
+    pub fn to_drops(&self) -> Result<u64, Error> {
+        match self {
+            Self::Xrp(value) => value.to_drops(),
+            Self::Drops(drops) if *drops > MAX_DROPS => Err(Error::validation(format!(
+                "{drops} drops is more XRP than exists"
+            ))),
+            Self::Drops(drops) => Ok(*drops),
+        }
+    }
+
+    pub fn to_drops_amount(&self) -> Result<DropsAmount, Error> {
+        DropsAmount::from_drops(self.to_drops()?)
+            .map_err(|e| Error::validation(format!("Invalid drops amount: {e}")))
+    }
+}
+
+impl fmt::Display for XrpAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xrp(value) => write!(f, "{value} XRP"),
+            Self::Drops(drops) => write!(f, "{drops} drops"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issued(value: &str) -> Result<(i64, i8), Error> {
+        let value = value.parse::<Decimal>()?.to_issued_value()?;
+        Ok((value.mantissa(), value.exponent()))
+    }
+
+    #[test]
+    fn normalizes_issued_values() {
+        assert_eq!(issued("1").unwrap(), (1_000_000_000_000_000, -15));
+        assert_eq!(issued("0.0001234").unwrap(), (1_234_000_000_000_000, -19));
+        assert_eq!(issued("-1.5").unwrap(), (-1_500_000_000_000_000, -15));
+        assert_eq!(issued("123456789012345").unwrap(), (1_234_567_890_123_450, -1));
+        assert_eq!(issued("1e80").unwrap(), (1_000_000_000_000_000, 65));
+        assert_eq!(issued("1e-81").unwrap(), (1_000_000_000_000_000, -96));
+        assert_eq!(Decimal::zero().to_issued_value().unwrap(), IssuedValue::zero());
+    }
+
+    #[test]
+    fn rejects_issued_values_the_ledger_cannot_hold() {
+        assert!(issued("1234567890123456").is_err());
+        assert!(issued("0.1234567890123456").is_err());
+        assert!(issued("1e-82").is_err());
+        assert!(issued("1e96").is_err());
+    }
+
+    #[test]
+    fn issued_values_round_trip() {
+        for value in ["1", "0.0001234", "-1.5", "999999999999999e80", "1e-81"] {
+            let decimal = value.parse::<Decimal>().unwrap();
+            assert_eq!(Decimal::from_issued_value(&decimal.to_issued_value().unwrap()), decimal);
+        }
+    }
+
+    #[test]
+    fn converts_xrp_to_drops() {
+        assert_eq!("1.000001".parse::<Decimal>().unwrap().to_drops().unwrap(), 1_000_001);
+        assert_eq!("100000000000".parse::<Decimal>().unwrap().to_drops().unwrap(), MAX_DROPS);
+        assert!("0.0000001".parse::<Decimal>().unwrap().to_drops().is_err());
+        assert!("-1".parse::<Decimal>().unwrap().to_drops().is_err());
+        assert!("100000000000.000001".parse::<Decimal>().unwrap().to_drops().is_err());
+        assert_eq!(Decimal::parse_drops("1000001").unwrap().to_string(), "1.000001");
+    }
+}
@@ -0,0 +1,450 @@
+//! Batch payouts from a CSV or JSON file, with a results file that records every
+//! submission so an interrupted batch can be resumed without paying anyone twice.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufReader, Write},
+    path::Path,
+    time::Duration,
+};
+use tracing::warn;
+use xrpl_types::{AccountId, PaymentTransaction};
+
+use super::{
+    address,
+    amount::Decimal,
+    error::Error,
+    memo::Memo,
+    token::Token,
+    transactions::WithMemos,
+    types::{PaymentOptions, TransactionOutcome},
+};
+
+/// One payout of a batch file.
+///
+/// In CSV files the header is `destination,currency,issuer,amount,destination_tag,memo`
+/// and optional columns may be left empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutRow {
+    /// Classic address or X-address of the recipient
+    pub destination: String,
+    /// `XRP` or the currency code of the token
+    pub currency: String,
+    /// Issuer of the token, empty for XRP
+    #[serde(default)]
+    pub issuer: Option<String>,
+    /// Decimal amount, in XRP rather than drops for XRP payouts
+    pub amount: String,
+    #[serde(default)]
+    pub destination_tag: Option<u32>,
+    /// Plain text memo attached to the payment
+    #[serde(default)]
+    pub memo: Option<String>,
+}
+
+impl PayoutRow {
+    /// The token paid out by this row
+    pub fn token(&self) -> Result<Token, Error> {
+        match (self.currency.eq_ignore_ascii_case("XRP"), self.issuer.as_deref()) {
+            (true, None) => Ok(Token::Xrp),
+            (true, Some(_)) => Err(Error::validation("XRP payouts take no issuer")),
+            (false, None) => Err(Error::validation(format!(
+                "Payouts of {} need an issuer",
+                self.currency
+            ))),
+            (false, Some(issuer)) => Token::issued(&self.currency, issuer),
+        }
+    }
+
+    /// The payment of this row from `account`
+    pub fn payment(&self, account: AccountId) -> Result<WithMemos<PaymentTransaction>, Error> {
+        let (destination, destination_tag) =
+            address::resolve_destination(&self.destination, self.destination_tag)?;
+
+        let value = self.amount.parse::<Decimal>()?;
+        if !value.is_positive() {
+            return Err(Error::validation(format!(
+                "Amount must be positive, got {}",
+                self.amount
+            )));
+        }
+        let amount = self.token()?.amount(&value)?;
+
+        let mut payment = PaymentTransaction::new(account, amount, destination);
+        payment.destination_tag = destination_tag;
+
+        self.options().apply(payment)
+    }
+
+    /// The destination tag and memo of this row
+    pub fn options(&self) -> PaymentOptions {
+        PaymentOptions {
+            destination_tag: self.destination_tag,
+            source_tag: None,
+            memos: self.memo.iter().map(|memo| Memo::text(memo)).collect(),
+        }
+    }
+}
+
+/// Read payout rows from a `.csv` or `.json` file, the latter holding an array of rows
+pub fn load_payouts(path: &Path) -> Result<Vec<PayoutRow>, Error> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        Error::validation(format!("Failed to read payouts from {}: {e}", path.display()))
+    };
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("csv") => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .map_err(|e| invalid(&e))?
+            .deserialize()
+            .collect::<Result<Vec<PayoutRow>, _>>()
+            .map_err(|e| invalid(&e)),
+        Some("json") => {
+            let file = fs::File::open(path).map_err(|e| invalid(&e))?;
+            serde_json::from_reader(BufReader::new(file)).map_err(|e| invalid(&e))
+        }
+        _ => Err(Error::validation(format!(
+            "Payout file {} must end in .csv or .json",
+            path.display()
+        ))),
+    }
+}
+
+/// Rate limits of a batch
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions {
+    /// Payments submitted but not final yet
+    pub max_in_flight: usize,
+    /// Pause between two submissions
+    pub submit_interval: Duration,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 10,
+            submit_interval: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Where a payout stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutStatus {
+    /// Signed and possibly submitted; a resumed batch resubmits the same blob
+    Pending,
+    /// Validated with `tesSUCCESS`
+    Succeeded,
+    /// Validated with a `tec` result or rejected, and not retried
+    Failed,
+    /// Never applied; a resumed batch pays it with a new transaction
+    Expired,
+}
+
+/// One line of the results file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutRecord {
+    /// Index of the row in the payout file, from 0
+    pub row: usize,
+    pub destination: String,
+    pub currency: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    pub amount: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_tag: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub status: PayoutStatus,
+    pub hash: String,
+    /// Engine result of a final payout, e.g. `tesSUCCESS`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// Signed transaction of a pending payout, hex encoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_blob: Option<String>,
+}
+
+impl PayoutRecord {
+    pub fn pending(row: usize, payout: &PayoutRow, hash: String, tx_blob: &[u8]) -> Self {
+        Self {
+            row,
+            destination: payout.destination.clone(),
+            currency: payout.currency.clone(),
+            issuer: payout.issuer.clone(),
+            amount: payout.amount.clone(),
+            destination_tag: payout.destination_tag,
+            memo: payout.memo.clone(),
+            status: PayoutStatus::Pending,
+            hash,
+            result: None,
+            tx_blob: Some(hex::encode_upper(tx_blob)),
+        }
+    }
+
+    /// The record of this payout once `outcome` is final
+    pub fn settle(&self, outcome: &TransactionOutcome) -> Self {
+        let (status, result) = match outcome {
+            _ if outcome.is_success() => (PayoutStatus::Succeeded, outcome.result()),
+            TransactionOutcome::Expired { .. } => (PayoutStatus::Expired, None),
+            _ => (PayoutStatus::Failed, outcome.result()),
+        };
+
+        Self {
+            status,
+            hash: outcome.hash().to_string(),
+            result: result.map(|result| format!("{result:?}")),
+            tx_blob: None,
+            ..self.clone()
+        }
+    }
+
+    /// Whether the payout is done and a resumed batch skips it
+    pub fn is_final(&self) -> bool {
+        matches!(self.status, PayoutStatus::Succeeded | PayoutStatus::Failed)
+    }
+
+    /// Whether this record was written for `payout`, so the payout file did not change
+    /// since. Every field of the row counts, as any edit makes it a different payment.
+    pub fn matches(&self, payout: &PayoutRow) -> bool {
+        let PayoutRow {
+            destination,
+            currency,
+            issuer,
+            amount,
+            destination_tag,
+            memo,
+        } = payout;
+
+        self.destination == *destination
+            && self.currency == *currency
+            && self.issuer == *issuer
+            && self.amount == *amount
+            && self.destination_tag == *destination_tag
+            && self.memo == *memo
+    }
+}
+
+/// Whether a row whose latest record is `record` still has to be paid: it was never
+/// signed, or its transaction expired without applying and a new one replaces it
+pub fn needs_payment(record: Option<&PayoutRecord>) -> bool {
+    record.is_none_or(|record| record.status == PayoutStatus::Expired)
+}
+
+/// Append-only JSON Lines results file, synced after every record
+pub struct ResultsFile {
+    file: fs::File,
+}
+
+impl ResultsFile {
+    /// Open or create the results file at `path`, returning the latest record of each row
+    pub fn open(path: &Path) -> Result<(Self, HashMap<usize, PayoutRecord>), Error> {
+        let failed = |e: &dyn std::fmt::Display| {
+            Error::validation(format!("Failed to open results file {}: {e}", path.display()))
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(failed(&e)),
+        };
+
+        let mut latest = HashMap::new();
+        let line_count = contents.lines().count();
+        let truncated = !contents.is_empty() && !contents.ends_with('\n');
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<PayoutRecord>(line) {
+                Ok(record) => {
+                    latest.insert(record.row, record);
+                }
+                // A crash can cut off the last line; its payout was not submitted yet
+                Err(_) if truncated && index + 1 == line_count => {
+                    warn!("Ignoring truncated last line of {}", path.display());
+                }
+                // Skipping any other record could pay its row twice
+                Err(e) => {
+                    return Err(Error::validation(format!(
+                        "Corrupt results file {}, line {}: {e}",
+                        path.display(),
+                        index + 1
+                    )));
+                }
+            }
+        }
+
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| failed(&e))?;
+        // Drop the cut off line, so that it is not followed by new records and read as a
+        // corrupt line by the next run
+        if truncated {
+            let complete_len = contents.rfind('\n').map_or(0, |end| end + 1);
+            file.set_len(complete_len as u64).map_err(|e| failed(&e))?;
+        }
+
+        Ok((Self { file }, latest))
+    }
+
+    pub fn append(&mut self, record: &PayoutRecord) -> Result<(), Error> {
+        let mut line = serde_json::to_string(record)
+            .map_err(|e| Error::validation(format!("Failed to serialize payout record: {e}")))?;
+        line.push('\n');
+
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|_| self.file.sync_data())
+            .map_err(|e| Error::validation(format!("Failed to write results file: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use xrpl_http_client::TransactionResult;
+
+    /// A fresh file path under the temp directory, unique to this process and test
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("shogun-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn row() -> PayoutRow {
+        PayoutRow {
+            destination: "ra5nK24KXen9AHvsdFTKHSANinZseWnPcX".to_string(),
+            currency: "USD".to_string(),
+            issuer: Some("rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn".to_string()),
+            amount: "12.5".to_string(),
+            destination_tag: Some(7),
+            memo: Some("invoice 42".to_string()),
+        }
+    }
+
+    fn pending(row_index: usize) -> PayoutRecord {
+        PayoutRecord::pending(row_index, &row(), "AB".repeat(32), &[0x12, 0x00])
+    }
+
+    #[test]
+    fn loads_csv_payouts() {
+        let path = temp_path("payouts.csv");
+        fs::write(
+            &path,
+            "destination,currency,issuer,amount,destination_tag,memo\n\
+             ra5nK24KXen9AHvsdFTKHSANinZseWnPcX, USD, rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn, 12.5, 7, invoice 42\n\
+             ra5nK24KXen9AHvsdFTKHSANinZseWnPcX,XRP,,1,,\n",
+        )
+        .unwrap();
+
+        let payouts = load_payouts(&path).unwrap();
+        assert_eq!(payouts[0], row());
+        assert_eq!(payouts[1].token().unwrap(), Token::Xrp);
+        assert_eq!((payouts[1].destination_tag, payouts[1].memo.as_deref()), (None, None));
+    }
+
+    #[test]
+    fn loads_json_payouts() {
+        let path = temp_path("payouts.json");
+        fs::write(&path, serde_json::to_string(&[row()]).unwrap()).unwrap();
+        assert_eq!(load_payouts(&path).unwrap(), vec![row()]);
+
+        let path = temp_path("payouts.txt");
+        fs::write(&path, "").unwrap();
+        assert!(load_payouts(&path).is_err());
+    }
+
+    #[test]
+    fn records_match_only_an_unchanged_row() {
+        let record = pending(0);
+        assert!(record.matches(&row()));
+
+        let changed = [
+            PayoutRow { amount: "12.6".to_string(), ..row() },
+            PayoutRow { issuer: None, ..row() },
+            PayoutRow { destination_tag: Some(8), ..row() },
+            PayoutRow { memo: None, ..row() },
+        ];
+        for payout in &changed {
+            assert!(!record.matches(payout));
+        }
+    }
+
+    #[test]
+    fn reads_back_records_up_to_a_truncated_last_line() {
+        let path = temp_path("truncated.jsonl");
+        let mut contents = String::new();
+        for record in [pending(0), pending(1)] {
+            contents.push_str(&serde_json::to_string(&record).unwrap());
+            contents.push('\n');
+        }
+        let cut_off = serde_json::to_string(&pending(2)).unwrap();
+        contents.push_str(&cut_off[..cut_off.len() / 2]);
+        fs::write(&path, contents).unwrap();
+
+        let (mut results, latest) = ResultsFile::open(&path).unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[&1].status, PayoutStatus::Pending);
+
+        // Appending starts on a new line, so the file reads back after the next crash
+        results.append(&pending(2)).unwrap();
+        drop(results);
+        let (_, latest) = ResultsFile::open(&path).unwrap();
+        assert_eq!(latest.len(), 3);
+    }
+
+    #[test]
+    fn rejects_a_corrupt_line_before_the_last() {
+        let path = temp_path("corrupt.jsonl");
+        let record = serde_json::to_string(&pending(0)).unwrap();
+        fs::write(&path, format!("{record}\n{{\"row\":\n{record}\n")).unwrap();
+
+        let error = ResultsFile::open(&path).err().unwrap().to_string();
+        assert!(error.contains("line 2"), "{error}");
+    }
+
+    /// A crash leaves a row pending. Its blob expired meanwhile, so resubmitting it is
+    /// reported as expired (the server answers `tefMAX_LEDGER`) and the row is paid again.
+    #[test]
+    fn resumed_payout_whose_blob_expired_is_paid_again() {
+        let path = temp_path("resumed.jsonl");
+        let (mut results, _) = ResultsFile::open(&path).unwrap();
+        results.append(&pending(0)).unwrap();
+        drop(results);
+
+        let (mut results, latest) = ResultsFile::open(&path).unwrap();
+        let record = &latest[&0];
+        assert!(!needs_payment(Some(record)));
+
+        let expired = record.settle(&TransactionOutcome::Expired {
+            hash: record.hash.clone(),
+            last_ledger_sequence: 100,
+        });
+        assert_eq!(expired.status, PayoutStatus::Expired);
+        assert!(expired.tx_blob.is_none());
+        results.append(&expired).unwrap();
+        drop(results);
+
+        let (_, latest) = ResultsFile::open(&path).unwrap();
+        assert!(needs_payment(latest.get(&0)));
+        assert!(needs_payment(latest.get(&1)));
+
+        let rejected = record.settle(&TransactionOutcome::Rejected {
+            hash: record.hash.clone(),
+            result: TransactionResult::temBAD_AMOUNT,
+            message: String::new(),
+        });
+        assert_eq!(rejected.status, PayoutStatus::Failed);
+        assert!(!needs_payment(Some(&rejected)));
+    }
+}
@@ -1,12 +1,22 @@
-use libsecp256k1::PublicKey;
 use tracing::{info, warn};
 use xrpl_http_client::{
     AccountCurrenciesRequest, AccountCurrenciesResponse, AccountInfoRequest, AccountInfoResponse,
-    AccountLinesRequest, AccountLinesResponse, Client,
-    TxRequest, TxResponse,
+    AccountLinesRequest, AccountLinesResponse, Client, FeeRequest, Meta,
+    TxRequest, TxResponse, error::Error as ClientError,
 };
 
-use crate::xrpl_http::types::FulfillmentDetails;
+use crate::xrpl_http::{
+    address,
+    amount,
+    error::Error,
+    fee::FeeEstimate,
+    rpc::{
+        RawAccountObjectsRequest, RawTxRequest, RipplePathFindRequest, RipplePathFindResponse,
+        ServerStateRequest, SimulateRequest, SimulateResponse,
+    },
+    token::Token,
+    types::{AccountReserves, FulfillmentDetails, SignerList, TransactionOutcome},
+};
 
 /// Service for read-only XRPL operations that only require HTTP client interactions
 pub struct ClientService {
@@ -24,7 +34,8 @@ impl ClientService {
     pub async fn get_account_currencies(
         &self,
         address: &str,
-    ) -> Result<AccountCurrenciesResponse, String> {
+    ) -> Result<AccountCurrenciesResponse, Error> {
+        let address = &address::to_classic_address(address)?;
         let req = AccountCurrenciesRequest::new(address);
 
         info!("Getting account currencies for address: {}", address);
@@ -32,13 +43,14 @@ impl ClientService {
             .client
             .call(req)
             .await
-            .map_err(|e| format!("Failed to get account currencies: {e}"))?;
+            .map_err(|e| Error::client("Failed to get account currencies", e))?;
 
         Ok(response)
     }
 
     /// Get account information including balance and sequence number
-    pub async fn get_account_info(&self, address: &str) -> Result<AccountInfoResponse, String> {
+    pub async fn get_account_info(&self, address: &str) -> Result<AccountInfoResponse, Error> {
+        let address = &address::to_classic_address(address)?;
         let req = AccountInfoRequest::new(address);
 
         info!("Getting account info for address: {}", address);
@@ -46,13 +58,14 @@ impl ClientService {
             .client
             .call(req)
             .await
-            .map_err(|e| format!("Failed to get account info: {e}"))?;
+            .map_err(|e| Error::client("Failed to get account info", e))?;
 
         Ok(response)
     }
 
     /// Get account trust lines
-    pub async fn get_account_lines(&self, address: &str) -> Result<AccountLinesResponse, String> {
+    pub async fn get_account_lines(&self, address: &str) -> Result<AccountLinesResponse, Error> {
+        let address = &address::to_classic_address(address)?;
         let req = AccountLinesRequest::new(address);
 
         info!("Getting account lines for address: {}", address);
@@ -60,54 +73,193 @@ impl ClientService {
             .client
             .call(req)
             .await
-            .map_err(|e| format!("Failed to get account lines: {e}"))?;
+            .map_err(|e| Error::client("Failed to get account lines", e))?;
 
         Ok(response)
     }
 
-    pub async fn inspect_tx(&self, tx_hash: &str) -> Result<TxResponse, String> {
+    /// Get the signer list of an account from its validated `account_objects`
+    pub async fn get_signer_list(&self, address: &str) -> Result<Option<SignerList>, Error> {
+        let address = &address::to_classic_address(address)?;
+        let req = RawAccountObjectsRequest::new(address, "signer_list");
+
+        info!("Getting signer list for address: {}", address);
+        let response = self
+            .client
+            .call(req)
+            .await
+            .map_err(|e| Error::client("Failed to get account objects", e))?;
+
+        response
+            .account_objects
+            .into_iter()
+            .next()
+            .map(|object| {
+                serde_json::from_value(object)
+                    .map_err(|e| Error::validation(format!("Invalid signer list: {e}")))
+            })
+            .transpose()
+    }
+
+    /// Get the ticket sequences an account holds <https://xrpl.org/tickets.html>
+    pub async fn get_tickets(&self, address: &str) -> Result<Vec<u32>, Error> {
+        let address = &address::to_classic_address(address)?;
+        let mut req = RawAccountObjectsRequest::new(address, "ticket");
+        let mut tickets = Vec::new();
+
+        info!("Getting tickets for address: {}", address);
+        loop {
+            let response = self
+                .client
+                .call(req.clone())
+                .await
+                .map_err(|e| Error::client("Failed to get account objects", e))?;
+
+            for object in &response.account_objects {
+                let ticket = object
+                    .get("TicketSequence")
+                    .and_then(serde_json::Value::as_u64)
+                    .ok_or_else(|| Error::validation("Ticket without TicketSequence"))?;
+                tickets.push(ticket as u32);
+            }
+
+            match response.marker {
+                Some(marker) => req.marker = Some(marker),
+                None => break,
+            }
+        }
+
+        Ok(tickets)
+    }
+
+    /// Get the current transaction costs and open ledger index
+    pub async fn get_fee(&self) -> Result<FeeEstimate, Error> {
+        let response = self
+            .client
+            .call(FeeRequest::new())
+            .await
+            .map_err(|e| Error::client("Failed to get fee", e))?;
+
+        FeeEstimate::from_response(&response)
+    }
+
+    /// Get the account reserves of the validated ledger
+    pub async fn get_reserves(&self) -> Result<AccountReserves, Error> {
+        let response = self
+            .client
+            .call(ServerStateRequest::default())
+            .await
+            .map_err(|e| Error::client("Failed to get server state", e))?;
+
+        let ledger = response.state.validated_ledger;
+        Ok(AccountReserves {
+            base: ledger.reserve_base,
+            owner: ledger.reserve_inc,
+        })
+    }
+
+    /// Ask the server to run an unsigned transaction blob without submitting it, or `None`
+    /// if the server does not support `simulate`
+    pub async fn simulate(&self, tx_blob: &[u8]) -> Result<Option<SimulateResponse>, Error> {
+        let req = SimulateRequest::new(hex::encode_upper(tx_blob));
+
+        match self.client.call(req).await {
+            Ok(response) => Ok(Some(response)),
+            Err(ClientError::Api(code)) if code == "unknownCmd" || code == "notImpl" => {
+                info!("Server cannot simulate transactions: {}", code);
+                Ok(None)
+            }
+            Err(e) => Err(Error::client("Failed to simulate transaction", e)),
+        }
+    }
+
+    /// Ways for `request`'s source account to deliver its destination amount, each with
+    /// the paths to take and the amount it costs
+    pub async fn find_paths(
+        &self,
+        request: RipplePathFindRequest,
+    ) -> Result<RipplePathFindResponse, Error> {
+        info!(
+            "Finding paths from {} to {} for {:?}",
+            request.source_account, request.destination_account, request.destination_amount
+        );
+
+        self.client
+            .call(request)
+            .await
+            .map_err(|e| Error::client("Failed to find paths", e))
+    }
+
+    pub async fn inspect_tx(&self, tx_hash: &str) -> Result<TxResponse, Error> {
         let req = TxRequest::new(tx_hash);
 
         let response = self
             .client
             .call(req)
             .await
-            .map_err(|e| format!("Failed to inspect transaction: {e}"))?;
+            .map_err(|e| Error::client("Failed to inspect transaction", e))?;
 
         Ok(response)
     }
 
-    pub async fn balance_change(&self, tx_hash: &str) -> Result<FulfillmentDetails, String> {
-        let tx_data = self.inspect_tx(tx_hash).await.unwrap().tx;
+    /// Outcome of a transaction if it is in a validated ledger, `None` while it is not
+    pub async fn get_validated_outcome(
+        &self,
+        tx_hash: &str,
+    ) -> Result<Option<TransactionOutcome>, Error> {
+        let req = RawTxRequest::new(tx_hash);
+
+        let response = match self.client.call(req).await {
+            Ok(response) => response,
+            Err(ClientError::Api(code)) if code == "txnNotFound" => return Ok(None),
+            Err(e) => return Err(Error::client("Failed to inspect transaction", e)),
+        };
+
+        if !response.validated {
+            return Ok(None);
+        }
+
+        let meta: Meta = response
+            .meta
+            .ok_or_else(|| Error::validation("Validated transaction has no metadata"))
+            .and_then(|meta| {
+                serde_json::from_value(meta)
+                    .map_err(|_| Error::validation("Invalid transaction metadata"))
+            })?;
+        let ledger_index = response
+            .ledger_index
+            .ok_or_else(|| Error::validation("Validated transaction has no ledger index"))?;
+
+        Ok(Some(TransactionOutcome::Validated {
+            hash: response.hash,
+            result: meta.transaction_result,
+            ledger_index,
+            meta,
+        }))
+    }
+
+    pub async fn balance_change(&self, tx_hash: &str) -> Result<FulfillmentDetails, Error> {
+        let tx_data = self.inspect_tx(tx_hash).await?.tx;
 
         match tx_data {
             xrpl_http_client::Transaction::Payment(payment_tx) => {
-                let amount_out = payment_tx
-                    .clone()
+                let delivered = payment_tx
                     .common
                     .meta
-                    .unwrap()
-                    .delivered_amount
-                    .unwrap()
-                    .size();
+                    .as_ref()
+                    .and_then(|meta| meta.delivered_amount.as_ref())
+                    .ok_or_else(|| Error::validation("Payment has no delivered amount"))?;
+                let send_max = payment_tx
+                    .send_max
+                    .as_ref()
+                    .ok_or_else(|| Error::validation("Payment has no SendMax"))?;
 
                 let fee = payment_tx.clone().common.fee;
 
-                let amount_in = payment_tx.clone().send_max.unwrap().size();
-
-                let (token_in, amount_in) = match payment_tx.clone().send_max.unwrap() {
-                    xrpl_http_client::Amount::Drops(_) => {
-                        ("XRP".to_string(), amount_in / 1000000.0)
-                    }
-                    xrpl_http_client::Amount::Issued(issued) => (issued.issuer, amount_in),
-                };
-
-                let (token_out, amount_out) = match payment_tx.clone().amount {
-                    xrpl_http_client::Amount::Drops(_) => {
-                        ("XRP".to_string(), amount_out / 1000000.0)
-                    }
-                    xrpl_http_client::Amount::Issued(issued) => (issued.issuer, amount_out),
-                };
+                let (token_in, amount_in) =
+                    (Token::of_amount(send_max), amount::value_of(send_max)?);
+                let (token_out, amount_out) =
+                    (Token::of_amount(delivered), amount::value_of(delivered)?);
                 
                 let xrp_first_epoch_timestamp = 946684800;
                 
@@ -115,9 +267,9 @@ impl ClientService {
                 
                 let details = FulfillmentDetails {
                     amount_out: amount_out.to_string(),
-                    token_out,
+                    token_out: token_out.to_string(),
                     amount_in: amount_in.to_string(),
-                    token_in,
+                    token_in: token_in.to_string(),
                     fee: fee.to_string(),
                     tx_signer: payment_tx.clone().common.account,
                     tx_timestamp
@@ -127,16 +279,16 @@ impl ClientService {
             }
             _ => {
                 warn!("Not a payment tx");
-                Err("Not a payment tx".to_string())
+                Err(Error::validation("Not a payment tx"))
             }
         }
     }
 
     /// Check if an account exists on the ledger
-    pub async fn account_exists(&self, address: &str) -> Result<bool, String> {
+    pub async fn account_exists(&self, address: &str) -> Result<bool, Error> {
         match self.get_account_info(address).await {
             Ok(_) => Ok(true),
-            Err(e) if e.contains("actNotFound") => Ok(false),
+            Err(e) if e.is_account_not_found() => Ok(false),
             Err(e) => Err(e),
         }
     }
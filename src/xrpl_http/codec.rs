@@ -0,0 +1,485 @@
+//! Decoding of serialized transactions back into their JSON representation.
+//!
+//! `xrpl_binary_codec` only serializes, so this walks the canonical binary format
+//! <https://xrpl.org/serialization.html> using its field definitions.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use xrpl_binary_codec::serializer::field_info::field_info_lookup;
+use xrpl_types::AccountId;
+
+use super::{amount::Decimal, error::Error};
+
+const TYPE_UINT16: u8 = 1;
+const TYPE_UINT32: u8 = 2;
+const TYPE_UINT64: u8 = 3;
+const TYPE_HASH128: u8 = 4;
+const TYPE_HASH256: u8 = 5;
+const TYPE_AMOUNT: u8 = 6;
+const TYPE_BLOB: u8 = 7;
+const TYPE_ACCOUNT_ID: u8 = 8;
+const TYPE_OBJECT: u8 = 14;
+const TYPE_ARRAY: u8 = 15;
+const TYPE_UINT8: u8 = 16;
+const TYPE_HASH160: u8 = 17;
+const TYPE_PATH_SET: u8 = 18;
+const TYPE_VECTOR256: u8 = 19;
+
+const OBJECT_END_MARKER: (u8, u8) = (TYPE_OBJECT, 1);
+const TXN_SIGNATURE: (u8, u8) = (TYPE_BLOB, 4);
+const SIGNERS: (u8, u8) = (TYPE_ARRAY, 3);
+const ARRAY_END_MARKER: (u8, u8) = (TYPE_ARRAY, 1);
+
+/// Names of fields missing from the `xrpl_binary_codec` definitions
+const EXTRA_FIELDS: &[(u8, u8, &str)] = &[(TYPE_PATH_SET, 1, "Paths"), (TYPE_VECTOR256, 1, "Indexes")];
+
+/// Transaction type codes <https://xrpl.org/transaction-types.html>
+const TRANSACTION_TYPES: &[(u16, &str)] = &[
+    (0, "Payment"),
+    (1, "EscrowCreate"),
+    (2, "EscrowFinish"),
+    (3, "AccountSet"),
+    (4, "EscrowCancel"),
+    (5, "SetRegularKey"),
+    (7, "OfferCreate"),
+    (8, "OfferCancel"),
+    (10, "TicketCreate"),
+    (12, "SignerListSet"),
+    (13, "PaymentChannelCreate"),
+    (14, "PaymentChannelFund"),
+    (15, "PaymentChannelClaim"),
+    (16, "CheckCreate"),
+    (17, "CheckCash"),
+    (18, "CheckCancel"),
+    (19, "DepositPreauth"),
+    (20, "TrustSet"),
+    (21, "AccountDelete"),
+    (25, "NFTokenMint"),
+    (26, "NFTokenBurn"),
+    (27, "NFTokenCreateOffer"),
+    (28, "NFTokenCancelOffer"),
+    (29, "NFTokenAcceptOffer"),
+];
+
+fn field_names() -> &'static HashMap<(u8, u8), String> {
+    static FIELD_NAMES: OnceLock<HashMap<(u8, u8), String>> = OnceLock::new();
+
+    FIELD_NAMES.get_or_init(|| {
+        let mut names: HashMap<(u8, u8), String> = field_info_lookup()
+            .iter()
+            .map(|(name, info)| ((info.field_type as u8, info.field_code.0), name.clone()))
+            .collect();
+        for (type_code, field_code, name) in EXTRA_FIELDS {
+            names.insert((*type_code, *field_code), name.to_string());
+        }
+        names
+    })
+}
+
+/// `tfPartialPayment` <https://xrpl.org/payment.html#payment-flags>
+pub const TF_PARTIAL_PAYMENT: u64 = 0x0002_0000;
+
+/// String field `name` of decoded transaction `fields`
+pub fn string_field<'a>(fields: &'a Map<String, Value>, name: &str) -> Result<&'a str, Error> {
+    fields
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::validation(format!("Transaction has no {name}")))
+}
+
+/// A decoded amount field: `None` for XRP or the currency and issuer of an issued
+/// amount, and the value, in XRP rather than drops for XRP
+pub fn amount_value(amount: &Value) -> Result<(Option<(String, String)>, Decimal), Error> {
+    let invalid = || Error::validation(format!("Invalid amount: {amount}"));
+
+    match amount {
+        Value::String(drops) => Ok((None, Decimal::parse_drops(drops)?)),
+        Value::Object(issued) => {
+            let field = |name: &str| issued.get(name).and_then(Value::as_str).ok_or_else(invalid);
+            let value = field("value")?.parse::<Decimal>()?;
+            let token = (field("currency")?.to_string(), field("issuer")?.to_string());
+            Ok((Some(token), value))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Name of a transaction type code, as used in `TransactionType`
+pub fn transaction_type_name(code: u16) -> Option<&'static str> {
+    TRANSACTION_TYPES
+        .iter()
+        .find(|(type_code, _)| *type_code == code)
+        .map(|(_, name)| *name)
+}
+
+/// Decode a serialized transaction into its JSON fields
+pub fn decode_transaction(bytes: &[u8]) -> Result<Map<String, Value>, Error> {
+    let mut reader = Reader { bytes, position: 0 };
+    let object = reader.read_object_fields(false)?;
+
+    if !reader.is_empty() {
+        return Err(Error::validation("Trailing bytes after transaction"));
+    }
+
+    Ok(object)
+}
+
+/// The serialized transaction without the fields that are not signed
+/// (`TxnSignature` and `Signers`), i.e. what the signature covers after its hash prefix
+pub fn signing_data(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut reader = Reader { bytes, position: 0 };
+    let mut data = Vec::with_capacity(bytes.len());
+
+    while !reader.is_empty() {
+        let start = reader.position;
+        let field_id = reader.read_field_id()?;
+        let name = field_names()
+            .get(&field_id)
+            .ok_or_else(|| {
+                Error::validation(format!(
+                    "Unknown field type {} code {}",
+                    field_id.0, field_id.1
+                ))
+            })?;
+        reader.read_value(name, field_id.0)?;
+
+        if field_id != TXN_SIGNATURE && field_id != SIGNERS {
+            data.extend_from_slice(&bytes[start..reader.position]);
+        }
+    }
+
+    Ok(data)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn read(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        let end = self.position + length;
+        if end > self.bytes.len() {
+            return Err(Error::validation(format!(
+                "Unexpected end of transaction at byte {}",
+                self.position
+            )));
+        }
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read(1)?[0])
+    }
+
+    /// Field id <https://xrpl.org/serialization.html#field-ids>
+    fn read_field_id(&mut self) -> Result<(u8, u8), Error> {
+        let first = self.read_u8()?;
+        let mut type_code = first >> 4;
+        let mut field_code = first & 0x0F;
+
+        if type_code == 0 {
+            type_code = self.read_u8()?;
+        }
+        if field_code == 0 {
+            field_code = self.read_u8()?;
+        }
+
+        Ok((type_code, field_code))
+    }
+
+    /// Length prefix <https://xrpl.org/serialization.html#length-prefixing>
+    fn read_vl_length(&mut self) -> Result<usize, Error> {
+        let b1 = self.read_u8()? as usize;
+        match b1 {
+            0..=192 => Ok(b1),
+            193..=240 => {
+                let b2 = self.read_u8()? as usize;
+                Ok(193 + (b1 - 193) * 256 + b2)
+            }
+            241..=254 => {
+                let b2 = self.read_u8()? as usize;
+                let b3 = self.read_u8()? as usize;
+                Ok(12481 + (b1 - 241) * 65536 + b2 * 256 + b3)
+            }
+            _ => Err(Error::validation(format!("Invalid length prefix: {b1}"))),
+        }
+    }
+
+    fn read_object_fields(&mut self, nested: bool) -> Result<Map<String, Value>, Error> {
+        let mut object = Map::new();
+
+        while !self.is_empty() {
+            let field_id = self.read_field_id()?;
+            if nested && field_id == OBJECT_END_MARKER {
+                return Ok(object);
+            }
+
+            let name = field_names()
+                .get(&field_id)
+                .ok_or_else(|| {
+                    Error::validation(format!(
+                        "Unknown field type {} code {}",
+                        field_id.0, field_id.1
+                    ))
+                })?;
+            let value = self.read_value(name, field_id.0)?;
+            object.insert(name.clone(), value);
+        }
+
+        if nested {
+            return Err(Error::validation("Missing object end marker"));
+        }
+
+        Ok(object)
+    }
+
+    fn read_value(&mut self, name: &str, type_code: u8) -> Result<Value, Error> {
+        match type_code {
+            TYPE_UINT8 => Ok(Value::from(self.read_u8()?)),
+            TYPE_UINT16 => {
+                let value = u16::from_be_bytes(self.read(2)?.try_into().unwrap());
+                if name == "TransactionType" {
+                    let type_name = transaction_type_name(value)
+                        .ok_or_else(|| {
+                            Error::validation(format!("Unknown transaction type: {value}"))
+                        })?;
+                    Ok(Value::from(type_name))
+                } else {
+                    Ok(Value::from(value))
+                }
+            }
+            TYPE_UINT32 => Ok(Value::from(u32::from_be_bytes(self.read(4)?.try_into().unwrap()))),
+            TYPE_UINT64 => Ok(Value::from(hex::encode_upper(self.read(8)?))),
+            TYPE_HASH128 => Ok(Value::from(hex::encode_upper(self.read(16)?))),
+            TYPE_HASH160 => Ok(Value::from(hex::encode_upper(self.read(20)?))),
+            TYPE_HASH256 => Ok(Value::from(hex::encode_upper(self.read(32)?))),
+            TYPE_AMOUNT => self.read_amount(),
+            TYPE_BLOB => {
+                let length = self.read_vl_length()?;
+                Ok(Value::from(hex::encode_upper(self.read(length)?)))
+            }
+            TYPE_ACCOUNT_ID => {
+                let length = self.read_vl_length()?;
+                Ok(Value::from(self.read_account_id(length)?))
+            }
+            TYPE_OBJECT => Ok(Value::Object(self.read_object_fields(true)?)),
+            TYPE_ARRAY => self.read_array(),
+            TYPE_PATH_SET => self.read_path_set(),
+            TYPE_VECTOR256 => {
+                let length = self.read_vl_length()?;
+                let hashes = self
+                    .read(length)?
+                    .chunks(32)
+                    .map(|hash| Value::from(hex::encode_upper(hash)))
+                    .collect();
+                Ok(Value::Array(hashes))
+            }
+            _ => Err(Error::validation(format!("Unsupported field type {type_code} for {name}"))),
+        }
+    }
+
+    fn read_account_id(&mut self, length: usize) -> Result<String, Error> {
+        let bytes: [u8; 20] = self
+            .read(length)?
+            .try_into()
+            .map_err(|_| Error::validation(format!("Invalid account id length: {length}")))?;
+        Ok(AccountId(bytes).to_address())
+    }
+
+    /// Amount <https://xrpl.org/serialization.html#amount-fields>
+    fn read_amount(&mut self) -> Result<Value, Error> {
+        let raw = u64::from_be_bytes(self.read(8)?.try_into().unwrap());
+        let is_issued = raw & 0x8000_0000_0000_0000 != 0;
+        let is_positive = raw & 0x4000_0000_0000_0000 != 0;
+
+        if !is_issued {
+            let drops = raw & 0x3FFF_FFFF_FFFF_FFFF;
+            let sign = if is_positive || drops == 0 { "" } else { "-" };
+            return Ok(Value::from(format!("{sign}{drops}")));
+        }
+
+        let mantissa = raw & 0x003F_FFFF_FFFF_FFFF;
+        let exponent = ((raw >> 54) & 0xFF) as i32 - 97;
+        let value = if mantissa == 0 {
+            "0".to_string()
+        } else {
+            let sign = if is_positive { "" } else { "-" };
+            format!("{sign}{}", format_decimal(mantissa, exponent))
+        };
+
+        let currency = currency_code(self.read(20)?);
+        let issuer = self.read_account_id(20)?;
+
+        let mut amount = Map::new();
+        amount.insert("currency".to_string(), Value::from(currency));
+        amount.insert("issuer".to_string(), Value::from(issuer));
+        amount.insert("value".to_string(), Value::from(value));
+        Ok(Value::Object(amount))
+    }
+
+    fn read_array(&mut self) -> Result<Value, Error> {
+        let mut elements = Vec::new();
+
+        loop {
+            let field_id = self.read_field_id()?;
+            if field_id == ARRAY_END_MARKER {
+                return Ok(Value::Array(elements));
+            }
+
+            let name = field_names()
+                .get(&field_id)
+                .ok_or_else(|| {
+                    Error::validation(format!(
+                        "Unknown field type {} code {}",
+                        field_id.0, field_id.1
+                    ))
+                })?;
+            let mut element = Map::new();
+            element.insert(name.clone(), Value::Object(self.read_object_fields(true)?));
+            elements.push(Value::Object(element));
+        }
+    }
+
+    /// Path set <https://xrpl.org/serialization.html#pathset-fields>
+    fn read_path_set(&mut self) -> Result<Value, Error> {
+        let mut paths = Vec::new();
+        let mut path = Vec::new();
+
+        loop {
+            let step_type = self.read_u8()?;
+            match step_type {
+                0x00 | 0xFF => {
+                    paths.push(Value::Array(std::mem::take(&mut path)));
+                    if step_type == 0x00 {
+                        return Ok(Value::Array(paths));
+                    }
+                }
+                _ => {
+                    let mut step = Map::new();
+                    if step_type & 0x01 != 0 {
+                        step.insert("account".to_string(), Value::from(self.read_account_id(20)?));
+                    }
+                    if step_type & 0x10 != 0 {
+                        step.insert("currency".to_string(), Value::from(currency_code(self.read(20)?)));
+                    }
+                    if step_type & 0x20 != 0 {
+                        step.insert("issuer".to_string(), Value::from(self.read_account_id(20)?));
+                    }
+                    path.push(Value::Object(step));
+                }
+            }
+        }
+    }
+}
+
+/// Currency code <https://xrpl.org/serialization.html#currency-codes>
+fn currency_code(bytes: &[u8]) -> String {
+    if bytes.iter().all(|b| *b == 0) {
+        return "XRP".to_string();
+    }
+
+    let is_standard = bytes[..12].iter().all(|b| *b == 0) && bytes[15..].iter().all(|b| *b == 0);
+    if is_standard {
+        String::from_utf8_lossy(&bytes[12..15]).to_string()
+    } else {
+        hex::encode_upper(bytes)
+    }
+}
+
+/// Format `mantissa * 10^exponent` as a plain decimal string
+fn format_decimal(mantissa: u64, exponent: i32) -> String {
+    let digits = mantissa.to_string();
+
+    if exponent >= 0 {
+        return format!("{digits}{}", "0".repeat(exponent as usize));
+    }
+
+    let scale = (-exponent) as usize;
+    let (integer, fraction) = if digits.len() > scale {
+        let split = digits.len() - scale;
+        (digits[..split].to_string(), digits[split..].to_string())
+    } else {
+        ("0".to_string(), format!("{}{digits}", "0".repeat(scale - digits.len())))
+    };
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer
+    } else {
+        format!("{integer}.{fraction}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xrpl_http::signer::verify_payload;
+    use serde_json::json;
+    use xrpl_binary_codec::hash::HASH_PREFIX_UNSIGNED_TRANSACTION_SINGLE;
+
+    /// Signed payment from the `sign` method example <https://xrpl.org/sign.html>
+    const SIGNED_PAYMENT: &str = "1200002280000000240000000361D4838D7EA4C6800000000000000000000000000055534400000000004B4E9C06F24296074F7BC48F92A97916C6DC5EA968400000000000000A732103AB40A0490F9B7ED8DF29D246BF2D6269820A0EE7742ACDD457BEA7C7D0931EDB74473045022100D184EB4AE5956FF600E7536EE459345C7BBCF097A84CC61A93B9AF7197EDB98702201CEA8009B7BEEBAA2AACC0359B41C427C1C5B550A4CA4B80CF2174AF2D6D5DCE81144B4E9C06F24296074F7BC48F92A97916C6DC5EA983143E9D4A2B8AA0780F682D136F7A56D6724EF53754";
+
+    const SIGNING_PUB_KEY: &str = "03AB40A0490F9B7ED8DF29D246BF2D6269820A0EE7742ACDD457BEA7C7D0931EDB";
+    const TXN_SIGNATURE: &str = "3045022100D184EB4AE5956FF600E7536EE459345C7BBCF097A84CC61A93B9AF7197EDB98702201CEA8009B7BEEBAA2AACC0359B41C427C1C5B550A4CA4B80CF2174AF2D6D5DCE";
+
+    #[test]
+    fn decodes_signed_payment() {
+        let decoded = decode_transaction(&hex::decode(SIGNED_PAYMENT).unwrap()).unwrap();
+
+        assert_eq!(
+            Value::Object(decoded),
+            json!({
+                "TransactionType": "Payment",
+                "Flags": 2147483648u32,
+                "Sequence": 3,
+                "Amount": {
+                    "currency": "USD",
+                    "issuer": "rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn",
+                    "value": "1",
+                },
+                "Fee": "10",
+                "SigningPubKey": SIGNING_PUB_KEY,
+                "TxnSignature": TXN_SIGNATURE,
+                "Account": "rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn",
+                "Destination": "ra5nK24KXen9AHvsdFTKHSANinZseWnPcX",
+            })
+        );
+    }
+
+    #[test]
+    fn signing_data_is_what_the_signature_covers() {
+        let blob = hex::decode(SIGNED_PAYMENT).unwrap();
+        let data = signing_data(&blob).unwrap();
+
+        let signature_field = hex::decode(format!("7447{TXN_SIGNATURE}")).unwrap();
+        assert_eq!(data.len(), blob.len() - signature_field.len());
+
+        let payload = [&HASH_PREFIX_UNSIGNED_TRANSACTION_SINGLE[..], &data].concat();
+        let public_key = hex::decode(SIGNING_PUB_KEY).unwrap();
+        let signature = hex::decode(TXN_SIGNATURE).unwrap();
+        assert!(verify_payload(&public_key, &payload, &signature).unwrap());
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut blob = hex::decode(SIGNED_PAYMENT).unwrap();
+        blob.push(0);
+        assert!(decode_transaction(&blob).is_err());
+    }
+
+    #[test]
+    fn formats_decimals() {
+        assert_eq!(format_decimal(1_000_000_000_000_000, -15), "1");
+        assert_eq!(format_decimal(1_234_000_000_000_000, -19), "0.0001234");
+        assert_eq!(format_decimal(15, 2), "1500");
+    }
+}
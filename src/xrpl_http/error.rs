@@ -0,0 +1,174 @@
+//! The error type of all XRPL operations.
+
+use std::fmt;
+
+use xrpl_http_client::{ResultCategory, TransactionResult, error::Error as ClientError};
+
+/// RPC error codes of a server that is busy or not in sync yet
+/// <https://xrpl.org/error-formatting.html#universal-errors>
+const RETRYABLE_RPC_CODES: [&str; 7] = [
+    "tooBusy",
+    "slowDown",
+    "noNetwork",
+    "notSynced",
+    "noCurrent",
+    "noClosed",
+    "lgrNotFound",
+];
+
+/// Errors of XRPL operations, classified so that callers can branch on the failure and
+/// know from [`Error::is_retryable`] whether trying again is safe, or from
+/// [`Error::is_outcome_unknown`] that a transaction may still apply
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// The server could not be reached or its response could not be read
+    Transport(String),
+    /// The server answered with an RPC error code, e.g. `actNotFound`
+    Rpc { code: String, context: String },
+    /// `tec`: the transaction was included in a ledger without effect, its fee and
+    /// sequence are consumed
+    Claimed { result: TransactionResult, message: String },
+    /// `tef`: the transaction was not applied and cannot be as it is, e.g. its sequence
+    /// is already used
+    Failed { result: TransactionResult, message: String },
+    /// `tel`: the server refused to relay the transaction, e.g. the fee is too low for
+    /// its current load
+    Local { result: TransactionResult, message: String },
+    /// `tem`: the transaction is malformed and can never succeed
+    Malformed { result: TransactionResult, message: String },
+    /// `ter`: the transaction could not be applied yet, e.g. an earlier sequence is missing
+    Retry { result: TransactionResult, message: String },
+    /// The transaction was not validated before its `LastLedgerSequence` passed, so it
+    /// never will be
+    Expired { hash: String, last_ledger_sequence: u32 },
+    /// Invalid input, e.g. a malformed address or amount, or an unexpected transaction
+    Validation(String),
+    /// Key derivation, signing or signature verification failed
+    Signing(String),
+    /// The keystore could not be read, written or decrypted
+    Keystore(String),
+}
+
+impl Error {
+    /// Classify a `client.call` failure, prefixing it with what was being done
+    pub fn client(context: &str, error: ClientError) -> Self {
+        match error {
+            ClientError::Api(code) if !code.starts_with("Status ") => Self::Rpc {
+                code,
+                context: context.to_string(),
+            },
+            error => Self::Transport(format!("{context}: {error}")),
+        }
+    }
+
+    /// The error of a transaction that failed with `result`
+    pub fn engine(result: TransactionResult, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match result.category() {
+            ResultCategory::Tec => Self::Claimed { result, message },
+            ResultCategory::Tel => Self::Local { result, message },
+            ResultCategory::Tem => Self::Malformed { result, message },
+            ResultCategory::Ter => Self::Retry { result, message },
+            ResultCategory::Tef | ResultCategory::Tes | ResultCategory::Other => {
+                Self::Failed { result, message }
+            }
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::Validation(message.into())
+    }
+
+    pub fn signing(message: impl Into<String>) -> Self {
+        Self::Signing(message.into())
+    }
+
+    pub fn keystore(message: impl Into<String>) -> Self {
+        Self::Keystore(message.into())
+    }
+
+    /// Whether the same operation may succeed if tried again without changing anything,
+    /// and no transaction it submitted can still apply: the server refused the request,
+    /// or the validated ledger passed `LastLedgerSequence` without the hash being found.
+    ///
+    /// Errors that leave a submitted transaction in flight are never retryable; see
+    /// [`Error::is_outcome_unknown`].
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Rpc { code, .. } => RETRYABLE_RPC_CODES.contains(&code.as_str()),
+            Self::Expired { .. } => true,
+            Self::Transport(_)
+            | Self::Local { .. }
+            | Self::Retry { .. }
+            | Self::Claimed { .. }
+            | Self::Failed { .. }
+            | Self::Malformed { .. }
+            | Self::Validation(_)
+            | Self::Signing(_)
+            | Self::Keystore(_) => false,
+        }
+    }
+
+    /// Whether a transaction may have been submitted and can still be validated, e.g.
+    /// after a lost connection, `terQUEUED` or a `tel` result. Look up its hash, or wait
+    /// for its `LastLedgerSequence` to pass, before preparing it again.
+    pub fn is_outcome_unknown(&self) -> bool {
+        matches!(
+            self,
+            Self::Transport(_) | Self::Local { .. } | Self::Retry { .. }
+        )
+    }
+
+    /// The RPC error code, if the server answered with one
+    pub fn rpc_code(&self) -> Option<&str> {
+        match self {
+            Self::Rpc { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// The engine result, if a transaction failed
+    pub fn engine_result(&self) -> Option<TransactionResult> {
+        match self {
+            Self::Claimed { result, .. }
+            | Self::Failed { result, .. }
+            | Self::Local { result, .. }
+            | Self::Malformed { result, .. }
+            | Self::Retry { result, .. } => Some(*result),
+            _ => None,
+        }
+    }
+
+    /// Whether the server reported that the account does not exist
+    pub fn is_account_not_found(&self) -> bool {
+        self.rpc_code() == Some("actNotFound")
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(msg)
+            | Error::Validation(msg)
+            | Error::Signing(msg)
+            | Error::Keystore(msg) => write!(f, "{msg}"),
+            Error::Rpc { code, context } => write!(f, "{context}: {code}"),
+            Error::Claimed { result, message }
+            | Error::Failed { result, message }
+            | Error::Local { result, message }
+            | Error::Malformed { result, message }
+            | Error::Retry { result, message } => {
+                write!(f, "Transaction failed with {result:?}: {message}")
+            }
+            Error::Expired {
+                hash,
+                last_ledger_sequence,
+            } => write!(
+                f,
+                "Transaction {hash} expired at ledger {last_ledger_sequence}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
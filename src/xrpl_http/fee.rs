@@ -0,0 +1,265 @@
+//! Transaction cost selection from the `fee` method <https://xrpl.org/fee.html>, with
+//! caps on what a single transaction and the account over an hour may spend.
+
+use std::{
+    collections::VecDeque,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use xrpl_http_client::FeeResponse;
+
+use super::error::Error;
+
+/// Default cap on the fee of one transaction, in drops
+pub const DEFAULT_MAX_FEE_PER_TRANSACTION: u64 = 1_000;
+
+/// Default cap on the fees spent over a rolling hour, in drops (1 XRP)
+pub const DEFAULT_MAX_FEES_PER_HOUR: u64 = 1_000_000;
+
+/// Window of the hourly fee budget
+const BUDGET_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// How much fee to offer relative to the current load of the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeePolicy {
+    /// The least the server accepts; under load the transaction waits in the queue
+    Minimum,
+    /// The median fee of the last validated ledger
+    Median,
+    /// Enough to get into the current open ledger, with a margin for further escalation
+    #[default]
+    Urgent,
+}
+
+impl FromStr for FeePolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minimum" => Ok(Self::Minimum),
+            "median" => Ok(Self::Median),
+            "urgent" => Ok(Self::Urgent),
+            _ => Err(Error::validation(format!(
+                "Unknown fee policy {s}, expected minimum, median or urgent"
+            ))),
+        }
+    }
+}
+
+/// Transaction costs reported by the `fee` method, in drops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// Cost of a reference transaction under minimum load
+    pub base_fee: u64,
+    /// Cost to be queued for a later ledger
+    pub minimum_fee: u64,
+    /// Median cost in the last validated ledger
+    pub median_fee: u64,
+    /// Cost to be included in the current open ledger
+    pub open_ledger_fee: u64,
+    /// Index of the current open ledger
+    pub ledger_current_index: u32,
+}
+
+impl FeeEstimate {
+    pub fn from_response(response: &FeeResponse) -> Result<Self, Error> {
+        let parse = |name: &str, value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|e| Error::Transport(format!("Invalid {name} in fee response: {e}")))
+        };
+
+        Ok(Self {
+            base_fee: parse("base_fee", &response.drops.base_fee)?,
+            minimum_fee: parse("minimum_fee", &response.drops.minimum_fee)?,
+            median_fee: parse("median_fee", &response.drops.median_fee)?,
+            open_ledger_fee: parse("open_ledger_fee", &response.drops.open_ledger_fee)?,
+            ledger_current_index: response.ledger_current_index,
+        })
+    }
+}
+
+/// Picks the fee of each transaction according to a [`FeePolicy`] and keeps the
+/// fees of submitted transactions within the configured caps
+#[derive(Debug)]
+pub struct FeeStrategy {
+    pub policy: FeePolicy,
+    /// Highest fee of a single transaction, in drops
+    pub max_fee_per_transaction: u64,
+    /// Highest sum of fees over a rolling hour, in drops
+    pub max_fees_per_hour: u64,
+    /// Fees charged within the last hour
+    spent: Mutex<VecDeque<(Instant, u64)>>,
+}
+
+impl FeeStrategy {
+    pub fn new(policy: FeePolicy, max_fee_per_transaction: u64, max_fees_per_hour: u64) -> Self {
+        Self {
+            policy,
+            max_fee_per_transaction,
+            max_fees_per_hour,
+            spent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Fee of a reference transaction under the current load, never above
+    /// `max_fee_per_transaction`
+    pub fn fee_for(&self, estimate: &FeeEstimate) -> u64 {
+        let floor = estimate.base_fee.max(estimate.minimum_fee);
+        let fee = match self.policy {
+            FeePolicy::Minimum => floor,
+            FeePolicy::Median => estimate.median_fee.max(floor),
+            FeePolicy::Urgent => (estimate.open_ledger_fee + estimate.open_ledger_fee / 2).max(floor),
+        };
+
+        if fee > self.max_fee_per_transaction {
+            warn!(
+                "Fee of {fee} drops for {:?} policy capped at {} drops",
+                self.policy, self.max_fee_per_transaction
+            );
+            return self.max_fee_per_transaction;
+        }
+
+        fee
+    }
+
+    /// Record `fee` as spent, or refuse it if it would exceed either cap
+    pub fn charge(&self, fee: u64) -> Result<(), Error> {
+        self.charge_at(fee, Instant::now())
+    }
+
+    fn charge_at(&self, fee: u64, now: Instant) -> Result<(), Error> {
+        if fee > self.max_fee_per_transaction {
+            return Err(Error::validation(format!(
+                "Fee of {fee} drops exceeds the maximum of {} drops per transaction",
+                self.max_fee_per_transaction
+            )));
+        }
+
+        let mut spent = self.spent.lock().unwrap();
+        while spent
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= BUDGET_WINDOW)
+        {
+            spent.pop_front();
+        }
+
+        let spent_last_hour: u64 = spent.iter().map(|(_, fee)| fee).sum();
+        if spent_last_hour + fee > self.max_fees_per_hour {
+            return Err(Error::validation(format!(
+                "Fee of {fee} drops exceeds the hourly budget: {spent_last_hour} of {} drops already spent",
+                self.max_fees_per_hour
+            )));
+        }
+
+        spent.push_back((now, fee));
+        Ok(())
+    }
+
+    /// Sum of the fees charged over the last hour, in drops
+    pub fn spent_last_hour(&self) -> u64 {
+        self.spent_at(Instant::now())
+    }
+
+    fn spent_at(&self, now: Instant) -> u64 {
+        self.spent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(at, _)| now.duration_since(*at) < BUDGET_WINDOW)
+            .map(|(_, fee)| fee)
+            .sum()
+    }
+}
+
+impl Default for FeeStrategy {
+    fn default() -> Self {
+        Self::new(
+            FeePolicy::default(),
+            DEFAULT_MAX_FEE_PER_TRANSACTION,
+            DEFAULT_MAX_FEES_PER_HOUR,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESTIMATE: FeeEstimate = FeeEstimate {
+        base_fee: 10,
+        minimum_fee: 12,
+        median_fee: 50,
+        open_ledger_fee: 200,
+        ledger_current_index: 1_000,
+    };
+
+    fn fee_for(policy: FeePolicy, estimate: &FeeEstimate) -> u64 {
+        FeeStrategy::new(policy, DEFAULT_MAX_FEE_PER_TRANSACTION, DEFAULT_MAX_FEES_PER_HOUR)
+            .fee_for(estimate)
+    }
+
+    #[test]
+    fn selects_fee_by_policy() {
+        assert_eq!(fee_for(FeePolicy::Minimum, &ESTIMATE), 12);
+        assert_eq!(fee_for(FeePolicy::Median, &ESTIMATE), 50);
+        assert_eq!(fee_for(FeePolicy::Urgent, &ESTIMATE), 300);
+    }
+
+    #[test]
+    fn never_selects_less_than_the_server_accepts() {
+        let idle = FeeEstimate {
+            minimum_fee: 10,
+            median_fee: 5000,
+            open_ledger_fee: 5,
+            ..ESTIMATE
+        };
+        assert_eq!(fee_for(FeePolicy::Minimum, &idle), 10);
+        assert_eq!(fee_for(FeePolicy::Urgent, &idle), 10);
+
+        // Capped at the per-transaction maximum
+        assert_eq!(fee_for(FeePolicy::Median, &idle), DEFAULT_MAX_FEE_PER_TRANSACTION);
+    }
+
+    #[test]
+    fn refuses_a_fee_above_the_transaction_cap() {
+        let strategy = FeeStrategy::new(FeePolicy::Urgent, 100, 1_000);
+
+        assert!(strategy.charge(101).is_err());
+        assert_eq!(strategy.spent_last_hour(), 0);
+        assert!(strategy.charge(100).is_ok());
+        assert_eq!(strategy.spent_last_hour(), 100);
+    }
+
+    #[test]
+    fn hourly_budget_frees_up_after_an_hour() {
+        let strategy = FeeStrategy::new(FeePolicy::Urgent, 100, 250);
+        let start = Instant::now();
+
+        strategy.charge_at(100, start).unwrap();
+        strategy.charge_at(100, start + Duration::from_secs(60)).unwrap();
+        assert!(strategy.charge_at(100, start + Duration::from_secs(120)).is_err());
+        strategy.charge_at(50, start + Duration::from_secs(120)).unwrap();
+        assert_eq!(strategy.spent_at(start + Duration::from_secs(120)), 250);
+
+        // The first charge leaves the window, the second one not yet
+        let later = start + BUDGET_WINDOW;
+        assert_eq!(strategy.spent_at(later), 150);
+        strategy.charge_at(100, later).unwrap();
+        assert!(strategy.charge_at(1, later).is_err());
+    }
+
+    #[test]
+    fn parses_fee_policies() {
+        assert_eq!("minimum".parse::<FeePolicy>().unwrap(), FeePolicy::Minimum);
+        assert_eq!("median".parse::<FeePolicy>().unwrap(), FeePolicy::Median);
+        assert_eq!("urgent".parse::<FeePolicy>().unwrap(), FeePolicy::Urgent);
+        assert!("Urgent".parse::<FeePolicy>().is_err());
+        assert!("".parse::<FeePolicy>().is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use aes_gcm::{
+    Aes256Gcm, KeyInit, Nonce,
+    aead::{Aead, Payload},
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::{RngCore, rngs::OsRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use zeroize::Zeroizing;
+
+use super::{
+    error::Error,
+    secret::SecretString,
+    signer::{KeyType, RippleSigner},
+};
+
+const KEYSTORE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Plaintext sealed next to the KDF parameters, so a wrong password is detected even
+/// when there is no account to decrypt
+const PASSWORD_CHECK: &[u8] = b"xrpl keystore password check";
+
+/// Argon2id parameters used to derive the encryption key from the password
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    salt: String,
+    /// Memory cost in KiB
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl KdfParams {
+    fn generate() -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        Self {
+            salt: hex::encode(salt),
+            m_cost: 64 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+
+    fn derive_key(&self, password: &SecretString) -> Result<Zeroizing<[u8; KEY_LEN]>, Error> {
+        let salt = hex::decode(&self.salt)
+            .map_err(|e| Error::keystore(format!("Invalid keystore salt: {e}")))?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_LEN))
+            .map_err(|e| Error::keystore(format!("Invalid keystore KDF parameters: {e}")))?;
+
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.expose_secret().as_bytes(), &salt, key.as_mut())
+            .map_err(|e| Error::keystore(format!("Failed to derive keystore key: {e}")))?;
+
+        Ok(key)
+    }
+}
+
+/// An encrypted account as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptedAccount {
+    name: String,
+    address: String,
+    key_type: KeyType,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedAccount {
+    /// The name and address are authenticated so entries cannot be swapped around in the file
+    fn associated_data(&self) -> Vec<u8> {
+        format!("{}:{}", self.name, self.address).into_bytes()
+    }
+}
+
+/// [`PASSWORD_CHECK`] sealed with the keystore key
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PasswordCheck {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    kdf: KdfParams,
+    check: PasswordCheck,
+    accounts: Vec<EncryptedAccount>,
+}
+
+/// Public information about an account in the keystore
+#[derive(Debug, Clone, Serialize)]
+pub struct KeystoreEntry {
+    pub name: String,
+    pub address: String,
+    pub key_type: KeyType,
+}
+
+/// Password-encrypted file holding the family seeds of several named accounts.
+///
+/// The key is derived with Argon2id and each seed is sealed with AES-256-GCM.
+pub struct Keystore {
+    path: PathBuf,
+    file: KeystoreFile,
+    key: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl Keystore {
+    /// Create a new, empty keystore at `path`. Fails if the file already exists.
+    pub fn create(path: impl AsRef<Path>, password: &SecretString) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            return Err(Error::keystore(format!("Keystore already exists: {}", path.display())));
+        }
+
+        let kdf = KdfParams::generate();
+        let key = kdf.derive_key(password)?;
+        let mut keystore = Self {
+            path,
+            file: KeystoreFile {
+                version: KEYSTORE_VERSION,
+                kdf,
+                // Sealed below, once the key is in place
+                check: PasswordCheck::default(),
+                accounts: Vec::new(),
+            },
+            key,
+        };
+        keystore.file.check = keystore.seal_check()?;
+        keystore.save()?;
+
+        Ok(keystore)
+    }
+
+    /// Open an existing keystore and check the password
+    pub fn open(path: impl AsRef<Path>, password: &SecretString) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| {
+                Error::keystore(format!("Failed to read keystore {}: {e}", path.display()))
+            })?;
+        let file: KeystoreFile = serde_json::from_str(&contents)
+            .map_err(|e| Error::keystore(format!("Invalid keystore {}: {e}", path.display())))?;
+
+        if file.version != KEYSTORE_VERSION {
+            return Err(Error::keystore(format!("Unsupported keystore version: {}", file.version)));
+        }
+
+        let key = file.kdf.derive_key(password)?;
+        let keystore = Self { path, file, key };
+        keystore.verify_check(&keystore.file.check)?;
+
+        Ok(keystore)
+    }
+
+    /// Open the keystore at `path`, creating an empty one if it does not exist yet
+    pub fn open_or_create(path: impl AsRef<Path>, password: &SecretString) -> Result<Self, Error> {
+        if path.as_ref().exists() {
+            Self::open(path, password)
+        } else {
+            Self::create(path, password)
+        }
+    }
+
+    /// List the accounts in the keystore without decrypting them
+    pub fn list(&self) -> Vec<KeystoreEntry> {
+        self.file
+            .accounts
+            .iter()
+            .map(|account| KeystoreEntry {
+                name: account.name.clone(),
+                address: account.address.clone(),
+                key_type: account.key_type,
+            })
+            .collect()
+    }
+
+    /// Encrypt `seed` under `name` and persist the keystore
+    pub fn add(&mut self, name: &str, seed: &SecretString) -> Result<KeystoreEntry, Error> {
+        if self.file.accounts.iter().any(|account| account.name == name) {
+            return Err(Error::keystore(format!("Account already exists in keystore: {name}")));
+        }
+
+        let signer = RippleSigner::from_seed(seed)?;
+        let mut account = EncryptedAccount {
+            name: name.to_string(),
+            address: signer.address().to_string(),
+            key_type: signer.key_type(),
+            nonce: String::new(),
+            ciphertext: String::new(),
+        };
+
+        let (nonce, ciphertext) =
+            self.seal(seed.expose_secret().as_bytes(), &account.associated_data())?;
+        account.nonce = nonce;
+        account.ciphertext = ciphertext;
+        self.file.accounts.push(account);
+        self.save()?;
+
+        Ok(KeystoreEntry {
+            name: name.to_string(),
+            address: signer.address().to_string(),
+            key_type: signer.key_type(),
+        })
+    }
+
+    /// Remove the account `name` and persist the keystore
+    pub fn remove(&mut self, name: &str) -> Result<(), Error> {
+        let position = self
+            .file
+            .accounts
+            .iter()
+            .position(|account| account.name == name)
+            .ok_or_else(|| Error::keystore(format!("Account not found in keystore: {name}")))?;
+
+        self.file.accounts.remove(position);
+        self.save()
+    }
+
+    /// Decrypt the account `name` into a signer
+    pub fn load(&self, name: &str) -> Result<RippleSigner, Error> {
+        let account = self
+            .file
+            .accounts
+            .iter()
+            .find(|account| account.name == name)
+            .ok_or_else(|| Error::keystore(format!("Account not found in keystore: {name}")))?;
+
+        let seed = self.decrypt(account)?;
+        let signer = RippleSigner::from_seed(&seed)?;
+
+        if signer.address() != account.address {
+            return Err(Error::keystore(format!(
+                "Keystore entry {name} does not match its address"
+            )));
+        }
+
+        Ok(signer)
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm, Error> {
+        Aes256Gcm::new_from_slice(self.key.as_ref())
+            .map_err(|e| Error::keystore(format!("Invalid keystore key: {e}")))
+    }
+
+    /// Encrypt `msg` under a fresh nonce, returning the hex encoded nonce and ciphertext
+    fn seal(&self, msg: &[u8], aad: &[u8]) -> Result<(String, String), Error> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()?
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg, aad })
+            .map_err(|e| Error::keystore(format!("Failed to encrypt keystore entry: {e}")))?;
+
+        Ok((hex::encode(nonce), hex::encode(ciphertext)))
+    }
+
+    /// Decrypt what [`Self::seal`] returned
+    fn unseal(
+        &self,
+        nonce: &str,
+        ciphertext: &str,
+        aad: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let nonce =
+            hex::decode(nonce).map_err(|e| Error::keystore(format!("Invalid nonce: {e}")))?;
+        if nonce.len() != NONCE_LEN {
+            return Err(Error::keystore("Invalid nonce length"));
+        }
+        let ciphertext = hex::decode(ciphertext)
+            .map_err(|e| Error::keystore(format!("Invalid ciphertext: {e}")))?;
+
+        self.cipher()?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| {
+                Error::keystore("Failed to decrypt keystore: wrong password or corrupted file")
+            })
+    }
+
+    fn seal_check(&self) -> Result<PasswordCheck, Error> {
+        let (nonce, ciphertext) = self.seal(PASSWORD_CHECK, b"")?;
+        Ok(PasswordCheck { nonce, ciphertext })
+    }
+
+    fn verify_check(&self, check: &PasswordCheck) -> Result<(), Error> {
+        let plaintext = self.unseal(&check.nonce, &check.ciphertext, b"")?;
+        if plaintext.as_slice() != PASSWORD_CHECK {
+            return Err(Error::keystore("Keystore password check does not match"));
+        }
+
+        Ok(())
+    }
+
+    fn decrypt(&self, account: &EncryptedAccount) -> Result<SecretString, Error> {
+        let plaintext =
+            self.unseal(&account.nonce, &account.ciphertext, &account.associated_data())?;
+
+        let seed = std::str::from_utf8(&plaintext)
+            .map_err(|e| Error::keystore(format!("Invalid seed encoding: {e}")))?;
+
+        Ok(SecretString::new(seed.to_string()))
+    }
+
+    /// Write the keystore to a temporary file next to it, then rename it into place
+    fn save(&self) -> Result<(), Error> {
+        let contents = serde_json::to_string_pretty(&self.file)
+            .map_err(|e| Error::keystore(format!("Failed to serialize keystore: {e}")))?;
+        let tmp_path = self.path.with_extension("tmp");
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options
+            .open(&tmp_path)
+            .map_err(|e| {
+                Error::keystore(format!("Failed to write keystore {}: {e}", tmp_path.display()))
+            })?;
+        file.write_all(contents.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| {
+                Error::keystore(format!("Failed to write keystore {}: {e}", tmp_path.display()))
+            })?;
+
+        fs::rename(&tmp_path, &self.path)
+            .map_err(|e| {
+                Error::keystore(format!(
+                    "Failed to write keystore {}: {e}",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+impl std::fmt::Debug for Keystore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keystore")
+            .field("path", &self.path)
+            .field("accounts", &self.list())
+            .field("key", &"[REDACTED]")
+            .finish()
+    }
+}
@@ -0,0 +1,251 @@
+//! Transaction memos <https://xrpl.org/transaction-common-fields.html#memos-field>, with
+//! helpers to carry plain text or JSON in them and to read them back from transactions.
+
+use serde::{Serialize as SerdeSerialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use xrpl_types::{
+    Blob,
+    serialize::{Serialize, Serializer},
+};
+
+use super::error::Error;
+
+/// Memo type of plain text memos
+pub const TEXT_MEMO_TYPE: &str = "text/plain";
+
+/// Memo type of JSON memos
+pub const JSON_MEMO_TYPE: &str = "application/json";
+
+/// Most bytes the memos of a transaction may take, counted as by [`serialized_size`]
+pub const MAX_MEMOS_SIZE: usize = 1024;
+
+/// A `Memo` inner object. Its fields hold arbitrary bytes, by convention UTF-8 text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Memo {
+    pub memo_type: Option<Blob>,
+    pub memo_data: Option<Blob>,
+    pub memo_format: Option<Blob>,
+}
+
+impl Memo {
+    /// A `text/plain` memo carrying `text`
+    pub fn text(text: &str) -> Self {
+        Self {
+            memo_type: Some(Blob(TEXT_MEMO_TYPE.as_bytes().to_vec())),
+            memo_data: Some(Blob(text.as_bytes().to_vec())),
+            memo_format: None,
+        }
+    }
+
+    /// An `application/json` memo carrying `value`, e.g. the order id of a fill
+    pub fn json<T: SerdeSerialize>(value: &T) -> Result<Self, Error> {
+        let data = serde_json::to_vec(value)
+            .map_err(|e| Error::validation(format!("Failed to serialize memo: {e}")))?;
+
+        Ok(Self {
+            memo_type: Some(Blob(JSON_MEMO_TYPE.as_bytes().to_vec())),
+            memo_data: Some(Blob(data)),
+            memo_format: None,
+        })
+    }
+
+    /// The memo type as text, e.g. `text/plain`
+    pub fn memo_type_text(&self) -> Result<Option<String>, Error> {
+        self.memo_type.as_ref().map(|blob| utf8(&blob.0)).transpose()
+    }
+
+    /// The memo data as text
+    pub fn data_text(&self) -> Result<Option<String>, Error> {
+        self.memo_data.as_ref().map(|blob| utf8(&blob.0)).transpose()
+    }
+
+    /// The memo data parsed as JSON
+    pub fn data_json<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let data = self
+            .memo_data
+            .as_ref()
+            .ok_or_else(|| Error::validation("Memo has no MemoData"))?;
+
+        serde_json::from_slice(&data.0)
+            .map_err(|e| Error::validation(format!("Invalid JSON memo: {e}")))
+    }
+
+    /// Read a `{"Memo": {"MemoType": ..., "MemoData": ...}}` entry of a transaction's
+    /// JSON, whose fields are hex encoded
+    pub fn from_json(entry: &Value) -> Result<Self, Error> {
+        let memo = entry
+            .get("Memo")
+            .and_then(Value::as_object)
+            .ok_or_else(|| Error::validation(format!("Invalid memo: {entry}")))?;
+        let field = |name: &str| {
+            memo.get(name)
+                .and_then(Value::as_str)
+                .map(|hex| decode_hex(hex).map(Blob))
+                .transpose()
+        };
+
+        Ok(Self {
+            memo_type: field("MemoType")?,
+            memo_data: field("MemoData")?,
+            memo_format: field("MemoFormat")?,
+        })
+    }
+}
+
+impl Serialize for Memo {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        if let Some(memo_type) = &self.memo_type {
+            serializer.serialize_blob("MemoType", memo_type)?;
+        }
+        if let Some(memo_data) = &self.memo_data {
+            serializer.serialize_blob("MemoData", memo_data)?;
+        }
+        if let Some(memo_format) = &self.memo_format {
+            serializer.serialize_blob("MemoFormat", memo_format)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The memos of a transaction in JSON form, e.g. from [`super::codec::decode_transaction`]
+/// or the `tx` method
+pub fn memos(transaction: &Map<String, Value>) -> Result<Vec<Memo>, Error> {
+    match transaction.get("Memos") {
+        None => Ok(Vec::new()),
+        Some(Value::Array(entries)) => entries.iter().map(Memo::from_json).collect(),
+        Some(other) => Err(Error::validation(format!("Invalid Memos: {other}"))),
+    }
+}
+
+/// Bytes `memos` take inside the `Memos` array of a transaction, which is what the ledger
+/// holds to [`MAX_MEMOS_SIZE`]: per memo its field id, its fields and an end marker
+pub fn serialized_size(memos: &[Memo]) -> usize {
+    memos
+        .iter()
+        .map(|memo| {
+            let fields = [&memo.memo_type, &memo.memo_data, &memo.memo_format];
+            let fields_size: usize = fields
+                .into_iter()
+                .flatten()
+                .map(|blob| 1 + length_prefix_size(blob.0.len()) + blob.0.len())
+                .sum();
+            2 + fields_size
+        })
+        .sum()
+}
+
+/// Refuse `memos` the ledger would reject for exceeding [`MAX_MEMOS_SIZE`]
+pub fn check_size(memos: &[Memo]) -> Result<(), Error> {
+    let size = serialized_size(memos);
+    if size > MAX_MEMOS_SIZE {
+        return Err(Error::validation(format!(
+            "Memos take {size} bytes, more than the limit of {MAX_MEMOS_SIZE}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Length prefix of a variable length field
+/// <https://xrpl.org/serialization.html#length-prefixing>
+fn length_prefix_size(length: usize) -> usize {
+    match length {
+        0..=192 => 1,
+        193..=12_480 => 2,
+        _ => 3,
+    }
+}
+
+/// `text` as the uppercase hex of its UTF-8 bytes, as memo fields appear in JSON
+pub fn encode_hex_text(text: &str) -> String {
+    hex::encode_upper(text.as_bytes())
+}
+
+/// The UTF-8 text of a hex encoded memo field
+pub fn decode_hex_text(hex: &str) -> Result<String, Error> {
+    utf8(&decode_hex(hex)?)
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(hex).map_err(|e| Error::validation(format!("Invalid hex in memo: {e}")))
+}
+
+fn utf8(bytes: &[u8]) -> Result<String, Error> {
+    String::from_utf8(bytes.to_vec())
+        .map_err(|e| Error::validation(format!("Memo is not UTF-8 text: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xrpl_http::transactions::WithMemos;
+    use serde_json::json;
+    use xrpl_binary_codec::serialize;
+    use xrpl_types::{AccountId, Amount, DropsAmount, PaymentTransaction};
+
+    /// The JSON entry of `memo` as a transaction carries it
+    fn entry(memo: &Memo) -> Value {
+        let field = |blob: &Option<Blob>| blob.as_ref().map(|blob| hex::encode_upper(&blob.0));
+        json!({
+            "Memo": {
+                "MemoType": field(&memo.memo_type),
+                "MemoData": field(&memo.memo_data),
+            }
+        })
+    }
+
+    #[test]
+    fn text_memos_round_trip_through_hex() {
+        assert_eq!(encode_hex_text(TEXT_MEMO_TYPE), "746578742F706C61696E");
+        assert_eq!(decode_hex_text("746578742F706C61696E").unwrap(), TEXT_MEMO_TYPE);
+        assert!(decode_hex_text("FF").is_err());
+
+        let memo = Memo::text("invoice 42");
+        let read_back = Memo::from_json(&entry(&memo)).unwrap();
+        assert_eq!(read_back, memo);
+        assert_eq!(read_back.memo_type_text().unwrap().as_deref(), Some(TEXT_MEMO_TYPE));
+        assert_eq!(read_back.data_text().unwrap().as_deref(), Some("invoice 42"));
+    }
+
+    #[test]
+    fn json_memos_round_trip_through_hex() {
+        let order = json!({"order_id": "0x12ab", "fill": 3});
+        let memo = Memo::json(&order).unwrap();
+
+        let mut transaction = Map::new();
+        transaction.insert("Memos".to_string(), json!([entry(&memo)]));
+        let memos = memos(&transaction).unwrap();
+
+        assert_eq!(memos, vec![memo]);
+        assert_eq!(memos[0].memo_type_text().unwrap().as_deref(), Some(JSON_MEMO_TYPE));
+        assert_eq!(memos[0].data_json::<Value>().unwrap(), order);
+        assert!(super::memos(&Map::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn counts_memo_size_as_serialized() {
+        let account = AccountId::from_address("rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn").unwrap();
+        let amount = Amount::Drops(DropsAmount::from_drops(1).unwrap());
+        let payment = PaymentTransaction::new(account, amount, account);
+        let memos = vec![Memo::text("a"), Memo::text(&"b".repeat(300)), Memo::json(&1).unwrap()];
+
+        let without = serialize::serialize(&WithMemos::new(payment.clone(), Vec::new())).unwrap();
+        let with = serialize::serialize(&WithMemos::new(payment, memos.clone())).unwrap();
+
+        // Besides the memos, the array adds its own field id and end marker
+        assert_eq!(with.len() - without.len() - 2, serialized_size(&memos));
+    }
+
+    #[test]
+    fn rejects_memos_over_the_limit() {
+        // Type: 1 + 1 + 10 bytes, data: 1 + 2 + length, and 2 for the object
+        let fits = Memo::text(&"x".repeat(MAX_MEMOS_SIZE - 17));
+        assert_eq!(serialized_size(std::slice::from_ref(&fits)), MAX_MEMOS_SIZE);
+        assert!(check_size(&[fits]).is_ok());
+
+        let too_large = Memo::text(&"x".repeat(MAX_MEMOS_SIZE - 16));
+        assert!(check_size(&[too_large]).is_err());
+        assert!(check_size(&[Memo::text(&"x".repeat(600)), Memo::text(&"y".repeat(600))]).is_err());
+    }
+}
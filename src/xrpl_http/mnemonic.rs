@@ -0,0 +1,146 @@
+use bip39::Mnemonic;
+use hmac::{Hmac, Mac};
+use libsecp256k1::{PublicKey, SecretKey};
+use sha2::Sha512;
+use std::fmt;
+use zeroize::Zeroizing;
+
+use super::error::Error;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// XRP coin type registered in SLIP-0044
+const XRP_COIN_TYPE: u32 = 144;
+const BIP44_PURPOSE: u32 = 44;
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// BIP44 derivation path `m/44'/144'/account'/0/index`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DerivationPath {
+    pub account: u32,
+    pub index: u32,
+}
+
+impl DerivationPath {
+    /// Create a new derivation path for the given account and address index
+    pub fn new(account: u32, index: u32) -> Self {
+        Self { account, index }
+    }
+
+    fn components(&self) -> Result<[u32; 5], Error> {
+        if self.account >= HARDENED_OFFSET {
+            return Err(Error::signing(format!("Account index out of range: {}", self.account)));
+        }
+        if self.index >= HARDENED_OFFSET {
+            return Err(Error::signing(format!("Address index out of range: {}", self.index)));
+        }
+
+        Ok([
+            BIP44_PURPOSE | HARDENED_OFFSET,
+            XRP_COIN_TYPE | HARDENED_OFFSET,
+            self.account | HARDENED_OFFSET,
+            0,
+            self.index,
+        ])
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m/44'/144'/{}'/0/{}", self.account, self.index)
+    }
+}
+
+/// Derive the secp256k1 secret key at `path` from a BIP39 mnemonic and optional passphrase
+pub fn derive_secret_key(
+    phrase: &str,
+    passphrase: Option<&str>,
+    path: DerivationPath,
+) -> Result<SecretKey, Error> {
+    let mnemonic = Mnemonic::parse(phrase)
+        .map_err(|e| Error::signing(format!("Invalid mnemonic: {e}")))?;
+    let seed = Zeroizing::new(mnemonic.to_seed(passphrase.unwrap_or("")));
+
+    let (mut secret_key, mut chain_code) = hmac_split(b"Bitcoin seed", seed.as_slice())?;
+
+    for child in path.components()? {
+        let mut data = Zeroizing::new(Vec::with_capacity(37));
+        if child >= HARDENED_OFFSET {
+            data.push(0);
+            data.extend_from_slice(&secret_key.serialize());
+        } else {
+            data.extend_from_slice(&PublicKey::from_secret_key(&secret_key).serialize_compressed());
+        }
+        data.extend_from_slice(&child.to_be_bytes());
+
+        let (tweak, child_chain_code) = hmac_split(chain_code.as_slice(), &data)?;
+        secret_key
+            .tweak_add_assign(&tweak)
+            .map_err(|e| Error::signing(format!("Failed to derive child key {path}: {e}")))?;
+        chain_code = child_chain_code;
+    }
+
+    Ok(secret_key)
+}
+
+/// HMAC-SHA512 split into a key (left half) and a chain code (right half) as in BIP32
+fn hmac_split(key: &[u8], data: &[u8]) -> Result<(SecretKey, Zeroizing<[u8; 32]>), Error> {
+    let mut mac = HmacSha512::new_from_slice(key)
+        .map_err(|e| Error::signing(format!("Invalid HMAC key: {e}")))?;
+    mac.update(data);
+    let output = Zeroizing::new(<[u8; 64]>::from(mac.finalize().into_bytes()));
+
+    let secret_key = SecretKey::parse_slice(&output[..32])
+        .map_err(|e| Error::signing(format!("Derived key is not a valid secp256k1 key: {e}")))?;
+    let mut chain_code = Zeroizing::new([0u8; 32]);
+    chain_code.copy_from_slice(&output[32..]);
+
+    Ok((secret_key, chain_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xrpl_http::signer::derive_address;
+
+    /// trezor-firmware device tests for Ripple addresses
+    const MNEMONIC: &str = "all all all all all all all all all all all all";
+
+    fn address_at(path: DerivationPath) -> String {
+        let secret_key = derive_secret_key(MNEMONIC, None, path).unwrap();
+        derive_address(&PublicKey::from_secret_key(&secret_key).serialize_compressed())
+    }
+
+    #[test]
+    fn master_key_matches_bip32_test_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let (secret_key, chain_code) = hmac_split(b"Bitcoin seed", &seed).unwrap();
+
+        assert_eq!(
+            hex::encode(secret_key.serialize()),
+            "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35"
+        );
+        assert_eq!(
+            hex::encode(chain_code.as_slice()),
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508"
+        );
+    }
+
+    #[test]
+    fn derives_known_xrp_addresses() {
+        assert_eq!(address_at(DerivationPath::new(0, 0)), "rNaqKtKrMSwpwZSzRckPf7S96DkimjkF4H");
+        assert_eq!(address_at(DerivationPath::new(0, 1)), "rBKz5MC2iXdoS3XgnNSYmF69K1Yo4NS3Ws");
+        assert_eq!(address_at(DerivationPath::new(1, 0)), "rJX2KwzaLJDyFhhtXKi3htaLfaUH2tptEX");
+    }
+
+    #[test]
+    fn rejects_hardened_indexes() {
+        assert!(DerivationPath::new(HARDENED_OFFSET, 0).components().is_err());
+        assert!(DerivationPath::new(0, HARDENED_OFFSET).components().is_err());
+    }
+
+    #[test]
+    fn formats_bip44_path() {
+        assert_eq!(DerivationPath::new(2, 7).to_string(), "m/44'/144'/2'/0/7");
+    }
+}
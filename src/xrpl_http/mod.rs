@@ -1,11 +1,46 @@
+pub mod address;
+pub mod amount;
+pub mod batch;
 pub mod client_service;
+pub mod codec;
+pub mod error;
+pub mod fee;
+pub mod keystore;
+pub mod memo;
+pub mod mnemonic;
+pub mod multisign;
+pub mod paths;
+pub mod remote_signer;
+pub mod rpc;
+pub mod secret;
+pub mod sequence;
 pub mod signer;
+pub mod simulate;
+pub mod token;
 pub mod transaction_service;
+pub mod transactions;
 pub mod types;
+pub mod verify;
 
+pub use amount::{Decimal, XrpAmount};
+pub use batch::{BatchOptions, PayoutRecord, PayoutRow, PayoutStatus};
 pub use client_service::ClientService;
-pub use signer::RippleSigner;
+pub use error::Error;
+pub use fee::{FeePolicy, FeeStrategy};
+pub use keystore::{Keystore, KeystoreEntry};
+pub use memo::Memo;
+pub use mnemonic::DerivationPath;
+pub use multisign::PartialSignature;
+pub use paths::{PathAlternative, PathSet, PathStep};
+pub use remote_signer::RemoteSigner;
+pub use secret::SecretString;
+pub use sequence::SequenceManager;
+pub use signer::{GeneratedWallet, KeyType, RippleSigner, Signer, verify_message};
+pub use token::Token;
 pub use transaction_service::TransactionService;
 pub use types::{
-     SwapError, SwapRequest,TrustLineRequest,
-};
\ No newline at end of file
+     AccountReserves, BalanceChange, OfflineSigningParams, PaymentOptions, RegularKeyRotation,
+     SequenceSource, SignedTransaction, SignerEntry, SignerList, Simulation, SimulationSource, SwapError,
+     SwapRequest, TransactionOutcome, TrustLineRequest,
+};
+pub use verify::{ExpectedPayment, VerifiedTransaction};
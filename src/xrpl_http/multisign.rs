@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use xrpl_binary_codec::serialize;
+use xrpl_types::{
+    AccountId, Blob, Transaction,
+    serialize::{Serialize as XrplSerialize, SerializeArray, Serializer},
+};
+
+use super::{error::Error, signer::Signer};
+
+/// Multi-signing prefix <https://xrpl.org/basic-data-types.html#hash-prefixes>
+pub const HASH_PREFIX_MULTI_SIGN: [u8; 4] = [0x53, 0x4D, 0x54, 0x00];
+
+/// One signer's contribution to a multi-signed transaction.
+///
+/// Serializable so quorum members can sign on separate machines and hand their
+/// signatures to whoever submits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PartialSignature {
+    /// Address of the signer list member
+    pub account: String,
+    /// Hex encoded public key of the signing key
+    pub signing_pub_key: String,
+    /// Hex encoded signature
+    pub txn_signature: String,
+}
+
+/// Sign `transaction` on behalf of its `Account` as one member of its signer list.
+///
+/// The transaction must already be prepared: sequence, fee and `LastLedgerSequence`
+/// are part of what every member signs.
+pub async fn sign_for<S, T>(signer: &S, transaction: &T) -> Result<PartialSignature, Error>
+where
+    S: Signer + ?Sized,
+    T: Transaction + Clone,
+{
+    let mut transaction = transaction.clone();
+    transaction.common_mut().signing_pub_key = Some(Blob(Vec::new()));
+    transaction.common_mut().txn_signature = None;
+
+    let signer_account = AccountId::from_address(signer.address())
+        .map_err(|e| Error::validation(format!("Invalid signer address: {e}")))?;
+    let serialized = serialize::serialize(&transaction)
+        .map_err(|e| Error::signing(format!("Failed to serialize transaction: {e}")))?;
+
+    let payload = [&HASH_PREFIX_MULTI_SIGN[..], &serialized, &signer_account.0].concat();
+    let signature = signer.sign_bytes(&payload).await?;
+
+    Ok(PartialSignature {
+        account: signer.address().to_string(),
+        signing_pub_key: hex::encode_upper(signer.public_key()),
+        txn_signature: hex::encode_upper(signature),
+    })
+}
+
+/// Merge partial signatures into a submittable multi-signed transaction blob.
+///
+/// Signers are sorted by account id as the ledger requires; duplicates are rejected.
+pub fn combine<T>(transaction: &T, signatures: &[PartialSignature]) -> Result<Vec<u8>, Error>
+where
+    T: Transaction + Clone,
+{
+    if signatures.is_empty() {
+        return Err(Error::signing("At least one signature is required"));
+    }
+
+    let mut signers = signatures
+        .iter()
+        .map(SignerFields::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    signers.sort_by_key(|signer| signer.account.0);
+
+    if let Some(duplicate) = signers.windows(2).find(|pair| pair[0].account.0 == pair[1].account.0) {
+        return Err(Error::signing(format!(
+            "Duplicate signature from {}",
+            duplicate[0].account.to_address()
+        )));
+    }
+
+    let mut transaction = transaction.clone();
+    transaction.common_mut().signing_pub_key = Some(Blob(Vec::new()));
+    transaction.common_mut().txn_signature = None;
+
+    let multi_signed = MultiSigned {
+        transaction: &transaction,
+        signers,
+    };
+
+    serialize::serialize(&multi_signed)
+        .map_err(|e| {
+            Error::signing(format!("Failed to serialize multi-signed transaction: {e}"))
+        })
+}
+
+/// A `Signer` inner object <https://xrpl.org/transaction-common-fields.html#signers-field>
+struct SignerFields {
+    account: AccountId,
+    signing_pub_key: Blob,
+    txn_signature: Blob,
+}
+
+impl SignerFields {
+    fn parse(signature: &PartialSignature) -> Result<Self, Error> {
+        Ok(Self {
+            account: AccountId::from_address(&signature.account)
+                .map_err(|e| Error::signing(format!("Invalid signer address: {e}")))?,
+            signing_pub_key: Blob(
+                hex::decode(&signature.signing_pub_key)
+                    .map_err(|e| Error::signing(format!("Invalid signer public key: {e}")))?,
+            ),
+            txn_signature: Blob(
+                hex::decode(&signature.txn_signature)
+                    .map_err(|e| Error::signing(format!("Invalid signer signature: {e}")))?,
+            ),
+        })
+    }
+}
+
+impl XrplSerialize for SignerFields {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_account_id("Account", AccountId(self.account.0))?;
+        serializer.serialize_blob("SigningPubKey", &self.signing_pub_key)?;
+        serializer.serialize_blob("TxnSignature", &self.txn_signature)
+    }
+}
+
+/// A transaction followed by its `Signers` array
+struct MultiSigned<'a, T> {
+    transaction: &'a T,
+    signers: Vec<SignerFields>,
+}
+
+impl<T: Transaction> XrplSerialize for MultiSigned<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        self.transaction.serialize(serializer)?;
+
+        let mut array = serializer.serialize_array("Signers")?;
+        for signer in &self.signers {
+            array.serialize_object("Signer", signer)?;
+        }
+        array.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xrpl_http::{
+        codec,
+        mnemonic::DerivationPath,
+        secret::SecretString,
+        signer::{RippleSigner, verify_payload},
+    };
+    use xrpl_types::{Amount, DropsAmount, PaymentTransaction};
+
+    /// Genesis account of a new ledger <https://xrpl.org/accounts.html#special-addresses>
+    const GENESIS_SEED: &str = "snoPBrXtMeMyMHUVTgbuqAfg1SUTb";
+    const GENESIS_ADDRESS: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+
+    fn signers() -> Vec<RippleSigner> {
+        let genesis = RippleSigner::from_seed(&SecretString::new(GENESIS_SEED.to_string())).unwrap();
+        let mnemonic = SecretString::new("all all all all all all all all all all all all".to_string());
+        let derived = RippleSigner::from_mnemonic(&mnemonic, None, DerivationPath::default()).unwrap();
+        vec![genesis, derived]
+    }
+
+    fn payment() -> PaymentTransaction {
+        let account = AccountId::from_address("rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn").unwrap();
+        let destination = AccountId::from_address("ra5nK24KXen9AHvsdFTKHSANinZseWnPcX").unwrap();
+        let mut payment = PaymentTransaction::new(
+            account,
+            Amount::Drops(DropsAmount::from_drops(1_000_000).unwrap()),
+            destination,
+        );
+        payment.common.sequence = Some(3);
+        payment.common.fee = Some(DropsAmount::from_drops(30).unwrap());
+        payment
+    }
+
+    #[test]
+    fn genesis_seed_derives_genesis_address() {
+        assert_eq!(signers()[0].address(), GENESIS_ADDRESS);
+    }
+
+    #[tokio::test]
+    async fn signatures_cover_the_transaction_and_signer_account() {
+        let payment = payment();
+        let signers = signers();
+
+        let mut signatures = Vec::new();
+        for signer in &signers {
+            signatures.push(sign_for(signer, &payment).await.unwrap());
+        }
+        let blob = combine(&payment, &signatures).unwrap();
+
+        let decoded = codec::decode_transaction(&blob).unwrap();
+        assert_eq!(decoded["SigningPubKey"], "");
+        let members = decoded["Signers"].as_array().unwrap();
+        assert_eq!(members.len(), 2);
+
+        let data = codec::signing_data(&blob).unwrap();
+        let mut previous = None;
+        for member in members {
+            let member = &member["Signer"];
+            let account = AccountId::from_address(member["Account"].as_str().unwrap()).unwrap();
+            assert!(previous < Some(account.0), "signers must be sorted by account id");
+            previous = Some(account.0);
+
+            let payload = [&HASH_PREFIX_MULTI_SIGN[..], &data, &account.0].concat();
+            let public_key = hex::decode(member["SigningPubKey"].as_str().unwrap()).unwrap();
+            let signature = hex::decode(member["TxnSignature"].as_str().unwrap()).unwrap();
+            assert!(verify_payload(&public_key, &payload, &signature).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicate_signers() {
+        let payment = payment();
+        let signature = sign_for(&signers()[0], &payment).await.unwrap();
+
+        assert!(combine(&payment, &[signature.clone(), signature]).is_err());
+        assert!(combine(&payment, &[]).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Payment paths <https://xrpl.org/paths.html>, as found by `ripple_path_find` and carried
+//! in the `Paths` field of cross-currency payments.
+//!
+//! `xrpl_binary_codec` cannot serialize path sets, so [`PathSet::encode`] produces the
+//! `Paths` field itself. Its type code sorts after every other field of a payment, so the
+//! canonical form of a payment with paths is its serialization followed by that field.
+
+use serde::{Deserialize, Serialize};
+
+use super::{address, amount, error::Error, token::Token};
+
+/// Field id of `Paths`: type code 18 (PathSet), field code 1
+const PATHS_FIELD_ID: [u8; 2] = [0x01, 0x12];
+
+const STEP_ACCOUNT: u8 = 0x01;
+const STEP_CURRENCY: u8 = 0x10;
+const STEP_ISSUER: u8 = 0x20;
+const PATH_BOUNDARY: u8 = 0xFF;
+const PATH_SET_END: u8 = 0x00;
+
+/// One step of a path: rippling through an account, or exchanging into a currency on the
+/// order books
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathStep {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+}
+
+/// Paths a payment may take besides its default path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PathSet(pub Vec<Vec<PathStep>>);
+
+impl PathSet {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The serialized `Paths` field <https://xrpl.org/serialization.html#pathset-fields>,
+    /// or nothing for an empty set, which payments leave out. Every path needs a step.
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.0.iter().any(Vec::is_empty) {
+            return Err(Error::validation("Path set has an empty path"));
+        }
+
+        let mut bytes = PATHS_FIELD_ID.to_vec();
+        for (index, path) in self.0.iter().enumerate() {
+            if index > 0 {
+                bytes.push(PATH_BOUNDARY);
+            }
+            for step in path {
+                encode_step(step, &mut bytes)?;
+            }
+        }
+        bytes.push(PATH_SET_END);
+
+        Ok(bytes)
+    }
+}
+
+fn encode_step(step: &PathStep, bytes: &mut Vec<u8>) -> Result<(), Error> {
+    let mut step_type = 0;
+    let mut fields = Vec::with_capacity(60);
+
+    if let Some(account) = &step.account {
+        step_type |= STEP_ACCOUNT;
+        fields.extend_from_slice(&address::decode_account_id(account)?.0);
+    }
+    if let Some(currency) = &step.currency {
+        step_type |= STEP_CURRENCY;
+        fields.extend_from_slice(&currency_code(currency)?);
+    }
+    if let Some(issuer) = &step.issuer {
+        step_type |= STEP_ISSUER;
+        fields.extend_from_slice(&address::decode_account_id(issuer)?.0);
+    }
+    if step_type == 0 {
+        return Err(Error::validation("Path step has no account, currency or issuer"));
+    }
+
+    bytes.push(step_type);
+    bytes.extend_from_slice(&fields);
+    Ok(())
+}
+
+/// Currency code <https://xrpl.org/serialization.html#currency-codes>
+fn currency_code(currency: &str) -> Result<[u8; 20], Error> {
+    let mut bytes = [0u8; 20];
+    if currency == "XRP" {
+        return Ok(bytes);
+    }
+
+    match currency.len() {
+        3 => bytes[12..15].copy_from_slice(currency.as_bytes()),
+        40 => {
+            let decoded = hex::decode(currency)
+                .map_err(|e| Error::validation(format!("Invalid currency code {currency}: {e}")))?;
+            bytes.copy_from_slice(&decoded);
+        }
+        _ => return Err(Error::validation(format!("Invalid currency code {currency}"))),
+    }
+
+    Ok(bytes)
+}
+
+/// One way to deliver an amount: the paths to take and what the source has to send
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathAlternative {
+    pub paths_computed: PathSet,
+    pub source_amount: xrpl_http_client::Amount,
+}
+
+impl PathAlternative {
+    /// The source amount in units of `token`, or `None` if it is paid in another token
+    pub fn source_value(&self, token: &Token) -> Result<Option<amount::Decimal>, Error> {
+        if Token::of_amount(&self.source_amount) != *token {
+            return Ok(None);
+        }
+
+        amount::value_of(&self.source_amount).map(Some)
+    }
+}
+
+/// A currency the source may spend, for `ripple_path_find`
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceCurrency {
+    pub currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+}
+
+impl From<&Token> for SourceCurrency {
+    fn from(token: &Token) -> Self {
+        Self {
+            currency: token.currency().to_string(),
+            issuer: token.issuer().map(str::to_string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xrpl_http::codec;
+
+    const ISSUER: &str = "rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn";
+    const ISSUER_ID: &str = "4B4E9C06F24296074F7BC48F92A97916C6DC5EA9";
+    const ACCOUNT: &str = "ra5nK24KXen9AHvsdFTKHSANinZseWnPcX";
+    const ACCOUNT_ID: &str = "3E9D4A2B8AA0780F682D136F7A56D6724EF53754";
+    const USD: &str = "0000000000000000000000005553440000000000";
+
+    fn step(account: Option<&str>, currency: Option<&str>, issuer: Option<&str>) -> PathStep {
+        PathStep {
+            account: account.map(str::to_string),
+            currency: currency.map(str::to_string),
+            issuer: issuer.map(str::to_string),
+        }
+    }
+
+    fn path_set() -> PathSet {
+        PathSet(vec![
+            vec![step(None, Some("USD"), Some(ISSUER))],
+            vec![step(Some(ACCOUNT), None, None), step(None, Some("XRP"), None)],
+        ])
+    }
+
+    #[test]
+    fn encodes_paths_field() {
+        let expected = format!(
+            "0112\
+             30{USD}{ISSUER_ID}\
+             FF\
+             01{ACCOUNT_ID}\
+             10{}\
+             00",
+            "00".repeat(20)
+        );
+
+        assert_eq!(hex::encode_upper(path_set().encode().unwrap()), expected);
+    }
+
+    #[test]
+    fn encoded_paths_decode_to_the_same_set() {
+        let decoded = codec::decode_transaction(&path_set().encode().unwrap()).unwrap();
+        let paths: PathSet = serde_json::from_value(decoded["Paths"].clone()).unwrap();
+
+        assert_eq!(paths, path_set());
+    }
+
+    #[test]
+    fn encodes_nonstandard_currency_codes() {
+        let currency = "0158415500000000C1F76FF6ECB0BAC600000000";
+        assert_eq!(hex::encode_upper(currency_code(currency).unwrap()), currency);
+        assert!(currency_code("USDC").is_err());
+    }
+
+    #[test]
+    fn empty_set_is_left_out() {
+        assert!(PathSet::default().encode().unwrap().is_empty());
+        assert!(PathSet(vec![vec![PathStep::default()]]).encode().is_err());
+        assert!(PathSet(vec![vec![]]).encode().is_err());
+
+        let usd = step(None, Some("USD"), Some(ISSUER));
+        assert!(PathSet(vec![vec![], vec![usd.clone()]]).encode().is_err());
+        assert!(PathSet(vec![vec![usd], vec![]]).encode().is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! Signing through a local daemon listening on a Unix socket.
+//!
+//! Each connection carries one newline-terminated JSON request and one JSON response:
+//!
+//! - `{"method":"public_key"}` -> `{"address":"r...","public_key":"<hex>"}`
+//! - `{"method":"sign","payload":"<hex>"}` -> `{"signature":"<hex>"}`
+//!
+//! Failures are answered with `{"error":"..."}`.
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    os::unix::fs::{DirBuilderExt, PermissionsExt},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+};
+use tracing::{info, warn};
+
+use super::{
+    error::Error,
+    signer::{RippleSigner, Signer},
+};
+
+/// How long the daemon waits for a client to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a client waits for the daemon to connect and answer
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest request line the daemon reads, well above any transaction payload
+const MAX_REQUEST_LEN: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum SignerRequest {
+    PublicKey,
+    Sign { payload: String },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SignerResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// [`Signer`] that forwards signing payloads to a signing daemon over a Unix socket
+#[derive(Debug)]
+pub struct RemoteSigner {
+    socket_path: PathBuf,
+    address: String,
+    public_key: Vec<u8>,
+}
+
+impl RemoteSigner {
+    /// Connect to the daemon at `socket_path` and fetch the account it signs for
+    pub async fn connect(socket_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let socket_path = socket_path.as_ref().to_path_buf();
+        let response = request(&socket_path, &SignerRequest::PublicKey).await?;
+
+        let address = response
+            .address
+            .ok_or_else(|| Error::signing("Signing daemon did not return an address"))?;
+        let public_key = response
+            .public_key
+            .ok_or_else(|| Error::signing("Signing daemon did not return a public key"))?;
+        let public_key = hex::decode(public_key).map_err(|e| {
+            Error::signing(format!("Invalid public key from signing daemon: {e}"))
+        })?;
+
+        info!("Connected to signing daemon for address: {}", address);
+
+        Ok(Self {
+            socket_path,
+            address,
+            public_key,
+        })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    fn sign_bytes<'a>(&'a self, payload: &'a [u8]) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move {
+            let sign_request = SignerRequest::Sign {
+                payload: hex::encode(payload),
+            };
+            let response = request(&self.socket_path, &sign_request).await?;
+            let signature = response
+                .signature
+                .ok_or_else(|| Error::signing("Signing daemon did not return a signature"))?;
+
+            hex::decode(signature)
+                .map_err(|e| Error::signing(format!("Invalid signature from signing daemon: {e}")))
+        })
+    }
+}
+
+async fn request(socket_path: &Path, request: &SignerRequest) -> Result<SignerResponse, Error> {
+    tokio::time::timeout(RESPONSE_TIMEOUT, exchange(socket_path, request))
+        .await
+        .map_err(|_| {
+            Error::Transport(format!(
+                "Timed out waiting for signing daemon {}",
+                socket_path.display()
+            ))
+        })?
+}
+
+async fn exchange(socket_path: &Path, request: &SignerRequest) -> Result<SignerResponse, Error> {
+    let stream = UnixStream::connect(socket_path)
+        .await
+        .map_err(|e| {
+            Error::Transport(format!(
+                "Failed to connect to signing daemon {}: {e}",
+                socket_path.display()
+            ))
+        })?;
+    let (reader, mut writer) = stream.into_split();
+
+    let mut line = serde_json::to_string(request)
+        .map_err(|e| Error::signing(format!("Failed to encode signer request: {e}")))?;
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| Error::Transport(format!("Failed to send signer request: {e}")))?;
+
+    let mut response = String::new();
+    BufReader::new(reader)
+        .read_line(&mut response)
+        .await
+        .map_err(|e| Error::Transport(format!("Failed to read signer response: {e}")))?;
+
+    let response: SignerResponse = serde_json::from_str(&response)
+        .map_err(|e| Error::signing(format!("Invalid signer response: {e}")))?;
+
+    match response.error {
+        Some(error) => Err(Error::signing(format!("Signing daemon error: {error}"))),
+        None => Ok(response),
+    }
+}
+
+/// Run a signing daemon for `signer` on `socket_path` until the listener fails.
+///
+/// Each connection is handled in its own task, so a client that stalls does not hold up
+/// the others.
+pub async fn serve(signer: RippleSigner, socket_path: impl AsRef<Path>) -> Result<(), Error> {
+    let socket_path = socket_path.as_ref();
+    let listener = bind_private(socket_path)?;
+
+    info!(
+        "Signing daemon for {} listening on {}",
+        signer.address(),
+        socket_path.display()
+    );
+
+    let signer = Arc::new(signer);
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| Error::signing(format!("Signing daemon accept failed: {e}")))?;
+
+        let signer = Arc::clone(&signer);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(&signer, stream).await {
+                warn!("Signing daemon request failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Bind a socket at `socket_path` that only this user can connect to.
+///
+/// The socket is bound inside a fresh `0700` directory and restricted to `0600` before it
+/// is linked to `socket_path`, so nobody else can connect in between.
+fn bind_private(socket_path: &Path) -> Result<UnixListener, Error> {
+    let failed = |e: &dyn std::fmt::Display| {
+        Error::signing(format!(
+            "Failed to bind signing daemon {}: {e}",
+            socket_path.display()
+        ))
+    };
+
+    let file_name = socket_path
+        .file_name()
+        .ok_or_else(|| failed(&"not a file path"))?
+        .to_string_lossy();
+    let staging_dir = socket_path.with_file_name(format!(".{file_name}.{}", std::process::id()));
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging_dir)
+        .map_err(|e| failed(&e))?;
+
+    let staging_path = staging_dir.join("socket");
+    let bound = UnixListener::bind(&staging_path)
+        .map_err(|e| failed(&e))
+        .and_then(|listener| {
+            fs::set_permissions(&staging_path, fs::Permissions::from_mode(0o600))
+                .map_err(|e| failed(&e))?;
+            // Unlike a rename, linking fails instead of replacing an existing file
+            fs::hard_link(&staging_path, socket_path).map_err(|e| failed(&e))?;
+            Ok(listener)
+        });
+
+    let _ = fs::remove_file(&staging_path);
+    let _ = fs::remove_dir(&staging_dir);
+
+    bound
+}
+
+async fn handle_connection(signer: &RippleSigner, stream: UnixStream) -> Result<(), Error> {
+    let (reader, mut writer) = stream.into_split();
+
+    let mut line = String::new();
+    let mut reader = BufReader::new(reader.take(MAX_REQUEST_LEN));
+    tokio::time::timeout(REQUEST_TIMEOUT, reader.read_line(&mut line))
+        .await
+        .map_err(|_| Error::signing("Timed out waiting for request"))?
+        .map_err(|e| Error::signing(format!("Failed to read request: {e}")))?;
+
+    let response = match serde_json::from_str::<SignerRequest>(&line) {
+        Ok(SignerRequest::PublicKey) => SignerResponse {
+            address: Some(signer.address().to_string()),
+            public_key: Some(hex::encode_upper(signer.public_key())),
+            ..Default::default()
+        },
+        Ok(SignerRequest::Sign { payload }) => match hex::decode(payload) {
+            Ok(payload) => {
+                info!("Signing payload of {} bytes", payload.len());
+                SignerResponse {
+                    signature: Some(hex::encode_upper(signer.sign_payload(&payload))),
+                    ..Default::default()
+                }
+            }
+            Err(e) => SignerResponse {
+                error: Some(format!("Invalid payload: {e}")),
+                ..Default::default()
+            },
+        },
+        Err(e) => SignerResponse {
+            error: Some(format!("Invalid request: {e}")),
+            ..Default::default()
+        },
+    };
+
+    let mut response = serde_json::to_string(&response)
+        .map_err(|e| Error::signing(format!("Failed to encode response: {e}")))?;
+    response.push('\n');
+    writer
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| Error::signing(format!("Failed to write response: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xrpl_http::signer::KeyType;
+
+    #[tokio::test]
+    async fn signs_through_a_private_socket() {
+        let dir = std::env::temp_dir().join(format!("shogun-signer-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let socket_path = dir.join("signer.sock");
+        let _ = fs::remove_file(&socket_path);
+
+        let signer = RippleSigner::generate(KeyType::Ed25519).unwrap().signer;
+        let address = signer.address().to_string();
+        // ed25519 signatures are deterministic
+        let expected = signer.sign_payload(b"payload");
+        let daemon = tokio::spawn(serve(signer, socket_path.clone()));
+        while !socket_path.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let mode = fs::metadata(&socket_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(bind_private(&socket_path).is_err());
+
+        let remote = RemoteSigner::connect(&socket_path).await.unwrap();
+        assert_eq!(remote.address(), address);
+        assert_eq!(remote.sign_bytes(b"payload").await.unwrap(), expected);
+
+        daemon.abort();
+        fs::remove_file(&socket_path).unwrap();
+    }
+}
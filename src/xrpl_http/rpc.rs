@@ -0,0 +1,220 @@
+//! JSON-RPC methods that are not modelled by `xrpl_http_client`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use xrpl_http_client::{Amount, Request, TransactionResult};
+
+use super::paths::{PathAlternative, SourceCurrency};
+
+/// Submit a multi-signed transaction in JSON form <https://xrpl.org/submit_multisigned.html>
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmitMultisignedRequest {
+    pub tx_json: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_hard: Option<bool>,
+}
+
+impl Request for SubmitMultisignedRequest {
+    type Response = SubmitMultisignedResponse;
+
+    fn method(&self) -> String {
+        "submit_multisigned".to_owned()
+    }
+}
+
+impl SubmitMultisignedRequest {
+    pub fn new(tx_json: Value) -> Self {
+        Self {
+            tx_json,
+            fail_hard: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubmitMultisignedResponse {
+    pub engine_result: String,
+    pub engine_result_code: i32,
+    pub engine_result_message: String,
+    pub tx_blob: String,
+    pub tx_json: Value,
+}
+
+/// `account_objects` returning the raw ledger objects, for object types
+/// `xrpl_http_client` does not model <https://xrpl.org/account_objects.html>
+#[derive(Debug, Clone, Serialize)]
+pub struct RawAccountObjectsRequest {
+    pub account: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker: Option<Value>,
+}
+
+impl Request for RawAccountObjectsRequest {
+    type Response = RawAccountObjectsResponse;
+
+    fn method(&self) -> String {
+        "account_objects".to_owned()
+    }
+}
+
+impl RawAccountObjectsRequest {
+    pub fn new(account: &str, object_type: &str) -> Self {
+        Self {
+            account: account.to_owned(),
+            object_type: Some(object_type.to_owned()),
+            ledger_index: Some("validated".to_owned()),
+            marker: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawAccountObjectsResponse {
+    pub account: String,
+    pub account_objects: Vec<Value>,
+    #[serde(default)]
+    pub marker: Option<Value>,
+}
+
+/// `tx` without decoding the transaction itself, so it works for every transaction type
+/// <https://xrpl.org/tx.html>
+#[derive(Debug, Clone, Serialize)]
+pub struct RawTxRequest {
+    pub transaction: String,
+}
+
+impl Request for RawTxRequest {
+    type Response = RawTxResponse;
+
+    fn method(&self) -> String {
+        "tx".to_owned()
+    }
+}
+
+impl RawTxRequest {
+    pub fn new(hash: &str) -> Self {
+        Self {
+            transaction: hash.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawTxResponse {
+    pub hash: String,
+    #[serde(default)]
+    pub ledger_index: Option<u32>,
+    #[serde(default)]
+    pub validated: bool,
+    #[serde(default)]
+    pub meta: Option<Value>,
+}
+
+/// Run an unsigned transaction against the current open ledger without submitting it
+/// <https://xrpl.org/docs/references/http-websocket-apis/public-api-methods/transaction-methods/simulate>
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulateRequest {
+    pub tx_blob: String,
+}
+
+impl Request for SimulateRequest {
+    type Response = SimulateResponse;
+
+    fn method(&self) -> String {
+        "simulate".to_owned()
+    }
+}
+
+impl SimulateRequest {
+    pub fn new(tx_blob: String) -> Self {
+        Self { tx_blob }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulateResponse {
+    pub engine_result: TransactionResult,
+    pub engine_result_code: i32,
+    pub engine_result_message: String,
+    #[serde(default)]
+    pub meta: Option<Value>,
+}
+
+/// `ripple_path_find` with the `send_max` and `source_currencies` that
+/// `xrpl_http_client`'s request cannot set <https://xrpl.org/ripple_path_find.html>
+#[derive(Debug, Clone, Serialize)]
+pub struct RipplePathFindRequest {
+    pub source_account: String,
+    pub destination_account: String,
+    pub destination_amount: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_max: Option<Amount>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub source_currencies: Vec<SourceCurrency>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_index: Option<String>,
+}
+
+impl Request for RipplePathFindRequest {
+    type Response = RipplePathFindResponse;
+
+    fn method(&self) -> String {
+        "ripple_path_find".to_owned()
+    }
+}
+
+impl RipplePathFindRequest {
+    pub fn new(source_account: &str, destination_account: &str, destination_amount: Amount) -> Self {
+        Self {
+            source_account: source_account.to_owned(),
+            destination_account: destination_account.to_owned(),
+            destination_amount,
+            send_max: None,
+            source_currencies: Vec::new(),
+            ledger_index: Some("current".to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RipplePathFindResponse {
+    pub alternatives: Vec<PathAlternative>,
+    pub destination_account: String,
+    #[serde(default)]
+    pub destination_currencies: Vec<String>,
+}
+
+/// `server_state`, for the reserves in drops that `server_info` does not model
+/// <https://xrpl.org/server_state.html>
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ServerStateRequest {}
+
+impl Request for ServerStateRequest {
+    type Response = ServerStateResponse;
+
+    fn method(&self) -> String {
+        "server_state".to_owned()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerStateResponse {
+    pub state: ServerState,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerState {
+    pub validated_ledger: ServerStateLedger,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerStateLedger {
+    pub seq: u32,
+    pub base_fee: u64,
+    pub reserve_base: u64,
+    pub reserve_inc: u64,
+}
@@ -0,0 +1,70 @@
+//! Handling of seeds and other secrets so they cannot end up in logs.
+
+use serde::Serializer;
+use std::{fmt, str::FromStr};
+use zeroize::Zeroizing;
+
+use super::error::Error;
+
+/// What [`SecretString`] prints instead of its contents.
+///
+/// The tracing layer drops any event containing it, see `crate::tracing`.
+pub const REDACTED_SECRET: &str = "[REDACTED SECRET]";
+
+/// A seed, mnemonic or password: zeroized on drop and never printed by `Debug` or `Display`
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    pub fn new(secret: String) -> Self {
+        Self(Zeroizing::new(secret))
+    }
+
+    /// Read a secret from the environment variable `name`
+    pub fn from_env(name: &str) -> Result<Self, Error> {
+        std::env::var(name)
+            .map(Self::new)
+            .map_err(|_| Error::validation(format!("{name} not set")))
+    }
+
+    /// Access the secret itself. Keep the borrow short and never log it.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Mutable access, e.g. to read a secret into it without an intermediate copy
+    pub fn expose_secret_mut(&mut self) -> &mut String {
+        &mut self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl FromStr for SecretString {
+    type Err = std::convert::Infallible;
+
+    fn from_str(secret: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(secret.to_string()))
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED_SECRET)
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED_SECRET)
+    }
+}
+
+/// Serialize the secret in clear, for the few outputs whose purpose is to show it
+/// (e.g. `#[serde(serialize_with = "secret::serialize_exposed")]`)
+pub fn serialize_exposed<S: Serializer>(secret: &SecretString, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(secret.expose_secret())
+}
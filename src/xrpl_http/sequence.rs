@@ -0,0 +1,356 @@
+//! Local allocation of account sequence numbers and tickets, so that several tasks can
+//! submit transactions for the same account at once without reusing either.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Mutex,
+};
+
+/// Sequence state of one account
+#[derive(Debug, Default)]
+struct AccountSequences {
+    /// Next sequence never handed out
+    next: u32,
+    /// Sequences handed out whose transaction is not final yet, with the
+    /// `LastLedgerSequence` of that transaction once it is signed
+    reserved: BTreeMap<u32, Option<u32>>,
+    /// Sequences given back unused; they are handed out again before `next` to fill the gap
+    released: BTreeSet<u32>,
+    /// Tickets of the account in the ledger that have not been handed out
+    tickets: BTreeSet<u32>,
+    /// Tickets handed out whose transaction is not final yet
+    taken_tickets: BTreeSet<u32>,
+    /// The local state may be ahead of the ledger, e.g. after a transaction expired
+    stale: bool,
+}
+
+/// Per-account sequence allocator shared by concurrent tasks.
+///
+/// The ledger is the source of truth: while an account has nothing in flight, the next
+/// reservation starts over from its ledger sequence (see [`Self::needs_sync`]). Only
+/// while transactions are pending are sequences allocated locally, until one of them
+/// expires or the server reports a sequence gap (see [`Self::invalidate`]).
+#[derive(Debug, Default)]
+pub struct SequenceManager {
+    accounts: Mutex<HashMap<String, AccountSequences>>,
+}
+
+impl SequenceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `address` has no transaction in flight or its state was invalidated, so its
+    /// sequence should be read from the ledger and passed to [`Self::sync`] before the next
+    /// reservation
+    pub fn needs_sync(&self, address: &str) -> bool {
+        let accounts = self.accounts.lock().unwrap();
+        accounts
+            .get(address)
+            .is_none_or(|account| account.reserved.is_empty() || account.stale)
+    }
+
+    /// Align the local state with the sequence of `address` in the ledger.
+    ///
+    /// Everything below `ledger_sequence` has been consumed. If nothing is in flight or the
+    /// state was invalidated, allocation restarts at `ledger_sequence`, skipping sequences
+    /// still in flight, which also closes any gap.
+    pub fn sync(&self, address: &str, ledger_sequence: u32) {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts.entry(address.to_string()).or_default();
+
+        account.reserved.retain(|sequence, _| *sequence >= ledger_sequence);
+        account.released.retain(|sequence| *sequence >= ledger_sequence);
+
+        if account.reserved.is_empty() || account.stale {
+            account.next = ledger_sequence;
+            account.released.clear();
+            account.stale = false;
+        } else {
+            account.next = account.next.max(ledger_sequence);
+        }
+    }
+
+    /// Reserve the lowest free sequence of `address`, or `None` if it was never synced
+    pub fn reserve(&self, address: &str) -> Option<u32> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts.get_mut(address)?;
+
+        let sequence = match account.released.pop_first() {
+            Some(sequence) => sequence,
+            None => {
+                while account.reserved.contains_key(&account.next) {
+                    account.next += 1;
+                }
+                let sequence = account.next;
+                account.next += 1;
+                sequence
+            }
+        };
+        account.reserved.insert(sequence, None);
+
+        Some(sequence)
+    }
+
+    /// The transaction with reserved `sequence` was signed with `last_ledger_sequence`, after
+    /// which [`Self::expire`] stops tracking it even if it is never confirmed or released,
+    /// e.g. when its blob was handed to a caller who did not submit it
+    pub fn set_last_ledger_sequence(
+        &self,
+        address: &str,
+        sequence: u32,
+        last_ledger_sequence: u32,
+    ) {
+        if let Some(expiry) = self
+            .accounts
+            .lock()
+            .unwrap()
+            .get_mut(address)
+            .and_then(|account| account.reserved.get_mut(&sequence))
+        {
+            *expiry = Some(last_ledger_sequence);
+        }
+    }
+
+    /// The lowest `LastLedgerSequence` among the transactions of `address` in flight
+    pub fn earliest_expiry(&self, address: &str) -> Option<u32> {
+        self.accounts
+            .lock()
+            .unwrap()
+            .get(address)
+            .and_then(|account| account.reserved.values().flatten().min().copied())
+    }
+
+    /// Stop tracking the transactions of `address` whose `LastLedgerSequence` is before
+    /// `validated_ledger`, as they can no longer apply, and resync with the ledger before
+    /// the next reservation
+    pub fn expire(&self, address: &str, validated_ledger: u32) {
+        if let Some(account) = self.accounts.lock().unwrap().get_mut(address) {
+            let in_flight = account.reserved.len();
+            account
+                .reserved
+                .retain(|_, expiry| expiry.is_none_or(|expiry| expiry >= validated_ledger));
+            if account.reserved.len() < in_flight {
+                account.stale = true;
+            }
+        }
+    }
+
+    /// The server reported that the local state of `address` is out of line with the
+    /// ledger (`terPRE_SEQ`, `tefPAST_SEQ` or an expired transaction): resync before the
+    /// next reservation
+    pub fn invalidate(&self, address: &str) {
+        if let Some(account) = self.accounts.lock().unwrap().get_mut(address) {
+            account.stale = true;
+        }
+    }
+
+    /// The transaction with `sequence` is final or out of our hands: stop tracking it
+    pub fn confirm(&self, address: &str, sequence: u32) {
+        if let Some(account) = self.accounts.lock().unwrap().get_mut(address) {
+            account.reserved.remove(&sequence);
+        }
+    }
+
+    /// The transaction with `sequence` will never apply: hand the sequence out again
+    pub fn release(&self, address: &str, sequence: u32) {
+        if let Some(account) = self.accounts.lock().unwrap().get_mut(address)
+            && account.reserved.remove(&sequence).is_some()
+        {
+            account.released.insert(sequence);
+        }
+    }
+
+    /// Replace the available tickets of `address` with those in the ledger, minus the ones
+    /// already handed out
+    pub fn sync_tickets(&self, address: &str, ledger_tickets: &[u32]) {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts.entry(address.to_string()).or_default();
+
+        account.taken_tickets.retain(|ticket| ledger_tickets.contains(ticket));
+        account.tickets = ledger_tickets
+            .iter()
+            .copied()
+            .filter(|ticket| !account.taken_tickets.contains(ticket))
+            .collect();
+    }
+
+    /// Take the lowest available ticket of `address`, or `None` if there is none left
+    pub fn take_ticket(&self, address: &str) -> Option<u32> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts.get_mut(address)?;
+
+        let ticket = account.tickets.pop_first()?;
+        account.taken_tickets.insert(ticket);
+
+        Some(ticket)
+    }
+
+    /// The transaction using `ticket` is final or out of our hands: stop tracking it
+    pub fn consume_ticket(&self, address: &str, ticket: u32) {
+        if let Some(account) = self.accounts.lock().unwrap().get_mut(address) {
+            account.taken_tickets.remove(&ticket);
+        }
+    }
+
+    /// The transaction using `ticket` will never apply: make the ticket available again
+    pub fn release_ticket(&self, address: &str, ticket: u32) {
+        if let Some(account) = self.accounts.lock().unwrap().get_mut(address)
+            && account.taken_tickets.remove(&ticket)
+        {
+            account.tickets.insert(ticket);
+        }
+    }
+
+    /// Number of tickets of `address` that can still be handed out
+    pub fn available_tickets(&self, address: &str) -> usize {
+        self.accounts
+            .lock()
+            .unwrap()
+            .get(address)
+            .map_or(0, |account| account.tickets.len())
+    }
+
+    /// Forget everything about `address`, e.g. after transactions were sent from elsewhere
+    pub fn reset(&self, address: &str) {
+        self.accounts.lock().unwrap().remove(address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    const ACCOUNT: &str = "rf1BiGeXwwQoi8Z2ueFYTEXSwuJYfV2Jpn";
+
+    fn synced(ledger_sequence: u32) -> SequenceManager {
+        let sequences = SequenceManager::new();
+        assert!(sequences.needs_sync(ACCOUNT));
+        sequences.sync(ACCOUNT, ledger_sequence);
+        sequences
+    }
+
+    #[test]
+    fn reserves_sequences_in_order() {
+        assert_eq!(SequenceManager::new().reserve(ACCOUNT), None);
+
+        let sequences = synced(10);
+        assert_eq!(sequences.reserve(ACCOUNT), Some(10));
+        assert_eq!(sequences.reserve(ACCOUNT), Some(11));
+        assert_eq!(sequences.reserve(ACCOUNT), Some(12));
+        assert!(!sequences.needs_sync(ACCOUNT));
+
+        for sequence in 10..=12 {
+            sequences.confirm(ACCOUNT, sequence);
+        }
+        assert!(sequences.needs_sync(ACCOUNT));
+    }
+
+    #[test]
+    fn hands_out_a_released_sequence_again() {
+        let sequences = synced(10);
+        for _ in 0..3 {
+            sequences.reserve(ACCOUNT);
+        }
+
+        sequences.release(ACCOUNT, 11);
+        assert_eq!(sequences.reserve(ACCOUNT), Some(11));
+        assert_eq!(sequences.reserve(ACCOUNT), Some(13));
+
+        // Releasing a sequence that is not reserved does not hand it out twice
+        sequences.release(ACCOUNT, 20);
+        assert_eq!(sequences.reserve(ACCOUNT), Some(14));
+    }
+
+    #[test]
+    fn resyncs_after_a_reservation_expires() {
+        let sequences = synced(10);
+        assert_eq!(sequences.reserve(ACCOUNT), Some(10));
+        assert_eq!(sequences.reserve(ACCOUNT), Some(11));
+        sequences.set_last_ledger_sequence(ACCOUNT, 10, 100);
+        sequences.set_last_ledger_sequence(ACCOUNT, 11, 200);
+        assert_eq!(sequences.earliest_expiry(ACCOUNT), Some(100));
+
+        sequences.expire(ACCOUNT, 100);
+        assert!(!sequences.needs_sync(ACCOUNT));
+
+        sequences.expire(ACCOUNT, 101);
+        assert!(sequences.needs_sync(ACCOUNT));
+        assert_eq!(sequences.earliest_expiry(ACCOUNT), Some(200));
+
+        // 10 never applied, so the ledger is still at 10 and 11 waits on it
+        sequences.sync(ACCOUNT, 10);
+        assert!(!sequences.needs_sync(ACCOUNT));
+        assert_eq!(sequences.reserve(ACCOUNT), Some(10));
+        assert_eq!(sequences.reserve(ACCOUNT), Some(12));
+    }
+
+    #[test]
+    fn resyncs_after_invalidate() {
+        let sequences = synced(10);
+        sequences.reserve(ACCOUNT);
+        sequences.reserve(ACCOUNT);
+        sequences.release(ACCOUNT, 11);
+
+        sequences.invalidate(ACCOUNT);
+        assert!(sequences.needs_sync(ACCOUNT));
+
+        // Transactions sent from elsewhere moved the account on
+        sequences.sync(ACCOUNT, 15);
+        assert_eq!(sequences.reserve(ACCOUNT), Some(15));
+        assert_eq!(sequences.reserve(ACCOUNT), Some(16));
+    }
+
+    #[test]
+    fn takes_releases_and_consumes_tickets() {
+        let sequences = SequenceManager::new();
+        assert_eq!(sequences.take_ticket(ACCOUNT), None);
+
+        sequences.sync_tickets(ACCOUNT, &[7, 5, 6]);
+        assert_eq!(sequences.available_tickets(ACCOUNT), 3);
+        assert_eq!(sequences.take_ticket(ACCOUNT), Some(5));
+        assert_eq!(sequences.take_ticket(ACCOUNT), Some(6));
+
+        sequences.release_ticket(ACCOUNT, 5);
+        assert_eq!(sequences.available_tickets(ACCOUNT), 2);
+        assert_eq!(sequences.take_ticket(ACCOUNT), Some(5));
+
+        sequences.consume_ticket(ACCOUNT, 6);
+        sequences.release_ticket(ACCOUNT, 6);
+        assert_eq!(sequences.available_tickets(ACCOUNT), 1);
+
+        // Tickets still taken are not handed out again by a sync
+        sequences.sync_tickets(ACCOUNT, &[5, 7, 8]);
+        assert_eq!(sequences.take_ticket(ACCOUNT), Some(7));
+        assert_eq!(sequences.take_ticket(ACCOUNT), Some(8));
+        assert_eq!(sequences.take_ticket(ACCOUNT), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_reservations_are_unique() {
+        let sequences = Arc::new(synced(1));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let sequences = Arc::clone(&sequences);
+                tokio::spawn(async move {
+                    let mut reserved = Vec::new();
+                    for _ in 0..100 {
+                        reserved.push(sequences.reserve(ACCOUNT).unwrap());
+                        tokio::task::yield_now().await;
+                    }
+                    reserved
+                })
+            })
+            .collect();
+
+        let mut reserved = Vec::new();
+        for task in tasks {
+            reserved.extend(task.await.unwrap());
+        }
+        reserved.sort_unstable();
+
+        assert_eq!(reserved, (1..=800).collect::<Vec<u32>>());
+    }
+}
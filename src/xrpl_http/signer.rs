@@ -1,47 +1,265 @@
-use libsecp256k1::{PublicKey, SecretKey};
-use ripple_keypairs::Seed;
-use tracing::info;
+use ed25519_dalek::Signer as _;
+use futures::future::BoxFuture;
+use libsecp256k1::{Message, PublicKey, SecretKey};
+use ripemd::Ripemd160;
+use ripple_keypairs::{Algorithm, Entropy, Seed};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
 use std::str::FromStr;
-use xrpl_binary_codec::sign;
-use xrpl_types::Transaction;
+use zeroize::Zeroizing;
+use xrpl_binary_codec::{hash, serialize};
+use xrpl_types::{AccountId, Blob, DropsAmount, Transaction};
+
+use super::{
+    address,
+    error::Error,
+    mnemonic::{self, DerivationPath},
+    paths::PathSet,
+    secret::{self, SecretString},
+    types::{OfflineSigningParams, SequenceSource, SignedTransaction},
+};
+
+/// Domain tag in front of off-chain messages, see [`message_digest`]
+pub const MESSAGE_TAG: &[u8] = b"XRPL Signed Message:\n";
+
+/// Signing algorithm of an XRPL key pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyType {
+    Secp256k1,
+    Ed25519,
+}
+
+impl KeyType {
+    fn from_algorithm(algorithm: &Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Secp256k1 => KeyType::Secp256k1,
+            Algorithm::Ed25519 => KeyType::Ed25519,
+        }
+    }
+
+    fn algorithm(&self) -> &'static Algorithm {
+        match self {
+            KeyType::Secp256k1 => &Algorithm::Secp256k1,
+            KeyType::Ed25519 => &Algorithm::Ed25519,
+        }
+    }
+}
+
+/// Signs transactions on behalf of an XRPL account.
+///
+/// Implementations only see the signing payload, so the key itself can live outside
+/// of this process (see [`RemoteSigner`](super::remote_signer::RemoteSigner)).
+pub trait Signer: Send + Sync + std::fmt::Debug {
+    /// Classic address of the account the signatures are for
+    fn address(&self) -> &str;
+
+    /// Public key as it appears in `SigningPubKey`
+    fn public_key(&self) -> &[u8];
+
+    /// Sign `payload`, the hash prefix followed by the serialized transaction
+    fn sign_bytes<'a>(&'a self, payload: &'a [u8]) -> BoxFuture<'a, Result<Vec<u8>, Error>>;
+
+    /// Algorithm of the signing key, inferred from the `0xED` prefix of ed25519 public keys
+    fn key_type(&self) -> KeyType {
+        match self.public_key().first() {
+            Some(0xED) => KeyType::Ed25519,
+            _ => KeyType::Secp256k1,
+        }
+    }
+}
+
+/// Fill in `SigningPubKey` and `TxnSignature` of `transaction` using any [`Signer`]
+pub async fn sign_transaction<S, T>(signer: &S, transaction: &mut T) -> Result<(), Error>
+where
+    S: Signer + ?Sized,
+    T: Transaction,
+{
+    sign_transaction_with_paths(signer, transaction, &PathSet::default()).await
+}
+
+/// Like [`sign_transaction`] for a payment taking `paths`, which the signature covers
+/// although `transaction` itself cannot carry them
+pub async fn sign_transaction_with_paths<S, T>(
+    signer: &S,
+    transaction: &mut T,
+    paths: &PathSet,
+) -> Result<(), Error>
+where
+    S: Signer + ?Sized,
+    T: Transaction,
+{
+    transaction.common_mut().signing_pub_key = Some(Blob(signer.public_key().to_vec()));
+
+    let mut serialized = serialize::serialize(transaction)
+        .map_err(|e| Error::signing(format!("Failed to sign transaction: {e}")))?;
+    serialized.extend(paths.encode()?);
+    let payload = [&hash::HASH_PREFIX_UNSIGNED_TRANSACTION_SINGLE[..], &serialized].concat();
+    let signature = signer.sign_bytes(&payload).await?;
+
+    transaction.common_mut().txn_signature = Some(Blob(signature));
+    Ok(())
+}
+
+/// Fill in sequence, fee and `LastLedgerSequence` from `params` and sign `transaction`
+/// without any network access
+pub async fn sign_offline<S, T>(
+    signer: &S,
+    mut transaction: T,
+    params: OfflineSigningParams,
+) -> Result<SignedTransaction, Error>
+where
+    S: Signer + ?Sized,
+    T: Transaction,
+{
+    if params.fee == 0 {
+        return Err(Error::validation("Fee must be greater than 0 drops"));
+    }
+    if params.last_ledger_sequence == 0 {
+        return Err(Error::validation("LastLedgerSequence must be greater than 0"));
+    }
+
+    let common = transaction.common_mut();
+    match params.sequence {
+        SequenceSource::Sequence(sequence) => {
+            common.sequence = Some(sequence);
+            common.ticket_sequence = None;
+        }
+        SequenceSource::Ticket(ticket) => {
+            common.sequence = Some(0);
+            common.ticket_sequence = Some(ticket);
+        }
+    }
+    common.fee = Some(
+        DropsAmount::from_drops(params.fee)
+            .map_err(|e| Error::validation(format!("Invalid fee: {e}")))?,
+    );
+    common.last_ledger_sequence = Some(params.last_ledger_sequence);
+
+    sign_transaction(signer, &mut transaction).await?;
+
+    let tx_blob = serialize::serialize(&transaction)
+        .map_err(|e| Error::signing(format!("Failed to serialize transaction: {e}")))?;
+    let hash = transaction_hash(&tx_blob);
+
+    Ok(SignedTransaction { tx_blob, hash })
+}
+
+/// A freshly generated account: its family seed, key pair and addresses
+#[derive(Serialize)]
+pub struct GeneratedWallet {
+    /// Family seed ("s...") from which the key pair is derived
+    #[serde(serialize_with = "secret::serialize_exposed")]
+    pub seed: SecretString,
+    pub key_type: KeyType,
+    /// Hex encoded `SigningPubKey`
+    pub public_key: String,
+    pub classic_address: String,
+    /// Mainnet X-address without a destination tag
+    pub x_address: String,
+    #[serde(skip)]
+    pub signer: RippleSigner,
+}
+
+impl std::fmt::Debug for GeneratedWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeneratedWallet")
+            .field("seed", &self.seed)
+            .field("key_type", &self.key_type)
+            .field("public_key", &self.public_key)
+            .field("classic_address", &self.classic_address)
+            .field("x_address", &self.x_address)
+            .finish()
+    }
+}
+
+/// Private key material for either supported algorithm
+enum SigningKey {
+    Secp256k1(SecretKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl SigningKey {
+    fn from_bytes(key_type: KeyType, secret_bytes: &[u8]) -> Result<Self, Error> {
+        match key_type {
+            KeyType::Secp256k1 => SecretKey::parse_slice(secret_bytes)
+                .map(SigningKey::Secp256k1)
+                .map_err(|e| Error::signing(format!("Failed to parse secret key: {e}"))),
+            KeyType::Ed25519 => {
+                let bytes: [u8; 32] = secret_bytes.try_into().map_err(|_| {
+                    Error::signing(format!(
+                        "Invalid ed25519 secret key length: expected 32 bytes, got {}",
+                        secret_bytes.len()
+                    ))
+                })?;
+                Ok(SigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&bytes)))
+            }
+        }
+    }
+
+    fn key_type(&self) -> KeyType {
+        match self {
+            SigningKey::Secp256k1(_) => KeyType::Secp256k1,
+            SigningKey::Ed25519(_) => KeyType::Ed25519,
+        }
+    }
+
+    /// Public key in the `SigningPubKey` format: 33 bytes, ed25519 keys prefixed with `0xED`
+    fn public_key(&self) -> Vec<u8> {
+        match self {
+            SigningKey::Secp256k1(secret_key) => PublicKey::from_secret_key(secret_key)
+                .serialize_compressed()
+                .to_vec(),
+            SigningKey::Ed25519(signing_key) => {
+                let mut public_key = vec![0xED];
+                public_key.extend_from_slice(signing_key.verifying_key().as_bytes());
+                public_key
+            }
+        }
+    }
+}
 
 /// Handles cryptographic operations for XRPL transactions
 pub struct RippleSigner {
     pub address: String,
-    pub secret_key: SecretKey,
-    pub public_key: PublicKey,
+    secret_key: SigningKey,
+    pub public_key: Vec<u8>,
 }
 
 impl RippleSigner {
-    /// Create a new signer from a seed string
-    pub fn from_seed(seed_str: &str) -> Result<Self, String> {
-        let seed = Seed::from_str(seed_str).map_err(|e| format!("Invalid seed format: {e}"))?;
-        
+    /// Create a new signer from a family seed
+    pub fn from_seed(seed: &SecretString) -> Result<Self, Error> {
+        let seed = Seed::from_str(seed.expose_secret())
+            .map_err(|e| Error::signing(format!("Invalid seed format: {e}")))?;
+        let key_type = KeyType::from_algorithm(seed.as_kind());
+
         let (private_key, public_key) = seed
             .derive_keypair()
-            .map_err(|e| format!("Failed to derive keypair: {e}"))?;
-        
+            .map_err(|e| Error::signing(format!("Failed to derive keypair: {e}")))?;
+
         let address = public_key.derive_address();
-        let secret_key_hex = private_key.to_string();
-        
-        let secret_bytes = hex::decode(secret_key_hex)
-            .map_err(|e| format!("Failed to decode secret key hex: {e}"))?;
-        
-        // For secp256k1, we need exactly 32 bytes
-        // If we have 33 bytes, take the first 32 (skip the prefix byte)
+        let secret_key_hex = Zeroizing::new(private_key.to_string());
+
+        let secret_bytes = Zeroizing::new(
+            hex::decode(secret_key_hex.as_str())
+                .map_err(|e| Error::signing(format!("Failed to decode secret key hex: {e}")))?,
+        );
+
+        // Private keys are 33 bytes: a `0x00` prefix for secp256k1, `0xED` for ed25519
         let key_bytes = if secret_bytes.len() == 33 {
-            &secret_bytes[1..] // Skip the first byte (ED prefix)
+            &secret_bytes[1..]
         } else if secret_bytes.len() == 32 {
-            &secret_bytes
+            &secret_bytes[..]
         } else {
-            return Err(format!("Invalid secret key length: expected 32 or 33 bytes, got {}", secret_bytes.len()));
+            return Err(Error::signing(format!(
+                "Invalid secret key length: expected 32 or 33 bytes, got {}",
+                secret_bytes.len()
+            )));
         };
-        
-        let secret_key = SecretKey::parse_slice(key_bytes)
-            .map_err(|e| format!("Failed to parse secret key: {e}"))?;
-        
-        let public_key = PublicKey::from_secret_key(&secret_key);
-        
+
+        let secret_key = SigningKey::from_bytes(key_type, key_bytes)?;
+        let public_key = secret_key.public_key();
+
         Ok(Self {
             address,
             secret_key,
@@ -49,12 +267,54 @@ impl RippleSigner {
         })
     }
 
-    /// Create a new signer from raw secret key bytes
-    pub fn from_secret_key_bytes(secret_bytes: &[u8], address: String) -> Result<Self, String> {
-        let secret_key = SecretKey::parse_slice(secret_bytes)
-            .map_err(|e| format!("Failed to parse secret key: {e}"))?;
+    /// Generate a new random account of the given key type
+    pub fn generate(key_type: KeyType) -> Result<GeneratedWallet, Error> {
+        let seed = SecretString::new(Seed::new(Entropy::Random, key_type.algorithm()).to_string());
+        let signer = Self::from_seed(&seed)?;
+        let classic_address = signer.address().to_string();
+        let x_address = address::encode_x_address(&classic_address, None, false)?;
+
+        Ok(GeneratedWallet {
+            seed,
+            key_type,
+            public_key: hex::encode_upper(signer.public_key()),
+            classic_address,
+            x_address,
+            signer,
+        })
+    }
+
+    /// Create a new secp256k1 signer from a BIP39 mnemonic at the BIP44 path
+    /// `m/44'/144'/account'/0/index`
+    pub fn from_mnemonic(
+        phrase: &SecretString,
+        passphrase: Option<&SecretString>,
+        path: DerivationPath,
+    ) -> Result<Self, Error> {
+        let secret_key = mnemonic::derive_secret_key(
+            phrase.expose_secret(),
+            passphrase.map(SecretString::expose_secret),
+            path,
+        )?;
+        let secret_key = SigningKey::Secp256k1(secret_key);
+        let public_key = secret_key.public_key();
+        let address = derive_address(&public_key);
 
-        let public_key = PublicKey::from_secret_key(&secret_key);
+        Ok(Self {
+            address,
+            secret_key,
+            public_key,
+        })
+    }
+
+    /// Create a new signer from raw secret key bytes
+    pub fn from_secret_key_bytes(
+        key_type: KeyType,
+        secret_bytes: &[u8],
+        address: String,
+    ) -> Result<Self, Error> {
+        let secret_key = SigningKey::from_bytes(key_type, secret_bytes)?;
+        let public_key = secret_key.public_key();
 
         Ok(Self {
             address,
@@ -63,10 +323,42 @@ impl RippleSigner {
         })
     }
 
-    /// Sign a transaction
-    pub fn sign_transaction<T: Transaction>(&self, transaction: &mut T) -> Result<(), String> {
-        sign::sign_transaction(transaction, &self.public_key, &self.secret_key)
-            .map_err(|e| format!("Failed to sign transaction: {e}"))
+    /// Sign for `address` instead of the account derived from this key, as its regular key
+    /// <https://xrpl.org/cryptographic-keys.html#regular-key-pair>
+    pub fn for_account(mut self, address: &str) -> Result<Self, Error> {
+        AccountId::from_address(address)
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+        self.address = address.to_string();
+
+        Ok(self)
+    }
+
+    /// Whether this key signs for an account other than the one it derives to
+    pub fn is_regular_key(&self) -> bool {
+        derive_address(&self.public_key) != self.address
+    }
+
+    /// Sign `payload` with the algorithm of this key.
+    ///
+    /// secp256k1 signs the SHA-512Half of the payload and returns a DER signature,
+    /// ed25519 signs the payload itself <https://xrpl.org/cryptographic-keys.html#signing-algorithms>
+    pub fn sign_payload(&self, payload: &[u8]) -> Vec<u8> {
+        match &self.secret_key {
+            SigningKey::Secp256k1(secret_key) => {
+                let digest = sha512_half(payload);
+                let (signature, _) = libsecp256k1::sign(&Message::parse(&digest), secret_key);
+                signature.serialize_der().as_ref().to_vec()
+            }
+            SigningKey::Ed25519(signing_key) => signing_key.sign(payload).to_bytes().to_vec(),
+        }
+    }
+
+    /// Sign an off-chain `message`, e.g. a challenge proving control of this address.
+    ///
+    /// What gets signed is the [`message_digest`] of `message`, never `message` itself, so
+    /// no challenge can make this key sign a transaction, claim or other ledger payload.
+    pub fn sign_message(&self, message: &[u8]) -> Vec<u8> {
+        self.sign_payload(&message_digest(message))
     }
 
     /// Get the account address
@@ -74,23 +366,165 @@ impl RippleSigner {
         &self.address
     }
 
-    /// Get the public key
-    pub fn public_key(&self) -> &PublicKey {
+    /// Get the algorithm of the signing key
+    pub fn key_type(&self) -> KeyType {
+        self.secret_key.key_type()
+    }
+
+    /// Get the public key as it appears in `SigningPubKey`
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+/// The in-memory [`Signer`]: the key is held by this process
+impl Signer for RippleSigner {
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn public_key(&self) -> &[u8] {
         &self.public_key
     }
 
-    /// Get a reference to the secret key (use with caution)
-    pub fn secret_key(&self) -> &SecretKey {
-        &self.secret_key
+    fn sign_bytes<'a>(&'a self, payload: &'a [u8]) -> BoxFuture<'a, Result<Vec<u8>, Error>> {
+        Box::pin(async move { Ok(self.sign_payload(payload)) })
+    }
+
+    fn key_type(&self) -> KeyType {
+        self.secret_key.key_type()
     }
 }
 
+/// First 32 bytes of the SHA-512 digest
+pub fn sha512_half(data: &[u8]) -> [u8; 32] {
+    let digest = Sha512::digest(data);
+    let mut half = [0u8; 32];
+    half.copy_from_slice(&digest[..32]);
+    half
+}
+
+/// Check `signature` over `payload` against a `SigningPubKey`, hashing the payload
+/// the same way as [`RippleSigner::sign_payload`]
+pub fn verify_payload(public_key: &[u8], payload: &[u8], signature: &[u8]) -> Result<bool, Error> {
+    match public_key.first() {
+        Some(0xED) => {
+            let key_bytes: [u8; 32] = public_key[1..]
+                .try_into()
+                .map_err(|_| {
+                    Error::signing(format!(
+                        "Invalid ed25519 public key length: {}",
+                        public_key.len()
+                    ))
+                })?;
+            let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&key_bytes)
+                .map_err(|e| Error::signing(format!("Invalid ed25519 public key: {e}")))?;
+            let signature = ed25519_dalek::Signature::from_slice(signature)
+                .map_err(|e| Error::signing(format!("Invalid ed25519 signature: {e}")))?;
+
+            Ok(verifying_key.verify_strict(payload, &signature).is_ok())
+        }
+        _ => {
+            let public_key = PublicKey::parse_slice(public_key, None)
+                .map_err(|e| Error::signing(format!("Invalid secp256k1 public key: {e}")))?;
+            let signature = libsecp256k1::Signature::parse_der(signature)
+                .map_err(|e| Error::signing(format!("Invalid secp256k1 signature: {e}")))?;
+            let digest = sha512_half(payload);
+
+            Ok(libsecp256k1::verify(&Message::parse(&digest), &signature, &public_key))
+        }
+    }
+}
+
+/// Verify a [`RippleSigner::sign_message`] signature from `address`.
+///
+/// Returns `false` if the signature does not match or `public_key` is not the master key of `address`.
+pub fn verify_message(
+    address: &str,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    if derive_address(public_key) != address {
+        return Ok(false);
+    }
+
+    verify_payload(public_key, &message_digest(message), signature)
+}
+
+/// SHA-512Half of `message` behind [`MESSAGE_TAG`] and its decimal length. No ledger
+/// payload is 32 bytes, so this never collides with one.
+pub fn message_digest(message: &[u8]) -> [u8; 32] {
+    let length = message.len().to_string();
+    sha512_half(&[MESSAGE_TAG, length.as_bytes(), message].concat())
+}
+
+/// Hash identifying a signed transaction blob <https://xrpl.org/basic-data-types.html#hashes>
+pub fn transaction_hash(tx_blob: &[u8]) -> String {
+    let hash = hash::hash(hash::HASH_PREFIX_SIGNED_TRANSACTION, tx_blob);
+    hex::encode_upper(hash.0)
+}
+
+/// Derive the classic address of a `SigningPubKey`: RIPEMD160(SHA256(public key))
+pub fn derive_address(public_key: &[u8]) -> String {
+    let account_hash = Ripemd160::digest(Sha256::digest(public_key));
+    let mut account_id = [0u8; 20];
+    account_id.copy_from_slice(&account_hash);
+
+    AccountId(account_id).to_address()
+}
+
 impl std::fmt::Debug for RippleSigner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RippleSigner")
             .field("address", &self.address)
+            .field("key_type", &self.key_type())
             .field("public_key", &"[REDACTED]")
             .field("secret_key", &"[REDACTED]")
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Payment channel claim prefix <https://xrpl.org/basic-data-types.html#hash-prefixes>
+    const HASH_PREFIX_PAYMENT_CHANNEL_CLAIM: [u8; 4] = *b"CLM\0";
+
+    fn signers() -> Vec<RippleSigner> {
+        [KeyType::Secp256k1, KeyType::Ed25519]
+            .into_iter()
+            .map(|key_type| RippleSigner::generate(key_type).unwrap().signer)
+            .collect()
+    }
+
+    /// The payload a channel claim of `drops` is signed over
+    fn claim_payload(drops: u64) -> Vec<u8> {
+        [&HASH_PREFIX_PAYMENT_CHANNEL_CLAIM[..], &[0x5A; 32], &drops.to_be_bytes()].concat()
+    }
+
+    #[test]
+    fn verifies_signed_messages() {
+        for signer in signers() {
+            let signature = signer.sign_message(b"login challenge 1234");
+            let verify = |message: &[u8]| {
+                verify_message(signer.address(), signer.public_key(), message, &signature).unwrap()
+            };
+
+            assert!(verify(b"login challenge 1234"));
+            assert!(!verify(b"login challenge 1235"));
+        }
+    }
+
+    #[test]
+    fn message_signature_is_not_a_claim_signature() {
+        for signer in signers() {
+            let claim = claim_payload(1_000_000_000);
+            let signature = signer.sign_message(&claim);
+
+            assert!(!verify_payload(signer.public_key(), &claim, &signature).unwrap());
+            assert_ne!(message_digest(&claim).len(), claim.len());
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Expected outcome of a transaction before it is submitted: balance changes from the
+//! metadata of `simulate`, and a local preflight for servers that cannot simulate.
+
+use serde_json::{Map, Value};
+use xrpl_http_client::TransactionResult;
+
+use super::{
+    amount::Decimal,
+    client_service::ClientService,
+    codec::{self, TF_PARTIAL_PAYMENT, string_field},
+    error::Error,
+    types::{BalanceChange, Simulation, SimulationSource},
+};
+
+/// Balance changes recorded in transaction metadata, from the `AccountRoot` and
+/// `RippleState` entries it modified, created or deleted
+pub fn balance_changes(meta: &Value) -> Vec<BalanceChange> {
+    let Some(nodes) = meta.get("AffectedNodes").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    let mut changes = Vec::new();
+    for node in nodes {
+        let Some((kind, node)) = node.as_object().and_then(|node| node.iter().next()) else {
+            continue;
+        };
+        let fields = node
+            .get(if kind == "CreatedNode" { "NewFields" } else { "FinalFields" })
+            .unwrap_or(&Value::Null);
+        let previous = node.get("PreviousFields").unwrap_or(&Value::Null);
+
+        // A created entry starts from zero; a modified one without a previous balance
+        // did not change it
+        let delta = |final_value: Option<Decimal>, previous_value: Option<Decimal>| {
+            let final_value = final_value?;
+            match previous_value {
+                Some(previous_value) => Some(final_value - previous_value),
+                None if kind == "CreatedNode" => Some(final_value),
+                None => None,
+            }
+        };
+
+        match node.get("LedgerEntryType").and_then(Value::as_str) {
+            Some("AccountRoot") => {
+                let xrp = |fields: &Value| {
+                    fields
+                        .get("Balance")
+                        .and_then(Value::as_str)
+                        .and_then(|drops| Decimal::parse_drops(drops).ok())
+                };
+                let (Some(account), Some(delta)) = (
+                    fields.get("Account").and_then(Value::as_str),
+                    delta(xrp(fields), xrp(previous)),
+                ) else {
+                    continue;
+                };
+
+                push_change(&mut changes, account, "XRP", None, delta);
+            }
+            Some("RippleState") => {
+                let value = |fields: &Value| {
+                    fields
+                        .pointer("/Balance/value")
+                        .and_then(Value::as_str)
+                        .and_then(|value| value.parse::<Decimal>().ok())
+                };
+                let (Some(currency), Some(low), Some(high), Some(delta)) = (
+                    fields.pointer("/Balance/currency").and_then(Value::as_str),
+                    fields.pointer("/LowLimit/issuer").and_then(Value::as_str),
+                    fields.pointer("/HighLimit/issuer").and_then(Value::as_str),
+                    delta(value(fields), value(previous)),
+                ) else {
+                    continue;
+                };
+
+                // A positive balance is held by the low account and owed by the high one
+                push_change(&mut changes, low, currency, Some(high), delta.clone());
+                push_change(&mut changes, high, currency, Some(low), -delta);
+            }
+            _ => {}
+        }
+    }
+
+    changes
+}
+
+fn push_change(
+    changes: &mut Vec<BalanceChange>,
+    account: &str,
+    currency: &str,
+    issuer: Option<&str>,
+    value: Decimal,
+) {
+    if value.is_zero() {
+        return;
+    }
+
+    changes.push(BalanceChange {
+        account: account.to_string(),
+        currency: currency.to_string(),
+        issuer: issuer.map(str::to_string),
+        value,
+    });
+}
+
+/// Check an unsigned transaction blob against the validated state of its accounts: that
+/// the account exists, the sequence is next, and the fee and a payment's amount are funded.
+///
+/// Paths, offers and most transaction specific rules are not checked, so a successful
+/// preflight can still fail on the ledger, e.g. with `tecPATH_PARTIAL`.
+pub async fn preflight(
+    client_service: &ClientService,
+    tx_blob: &[u8],
+) -> Result<Simulation, Error> {
+    let fields = codec::decode_transaction(tx_blob)?;
+    let account = string_field(&fields, "Account")?;
+
+    let account_root = match client_service.get_account_info(account).await {
+        Ok(response) => response.account_data,
+        Err(e) if e.is_account_not_found() => {
+            return Ok(failed(
+                TransactionResult::terNO_ACCOUNT,
+                "The source account does not exist",
+            ));
+        }
+        Err(e) => return Err(e),
+    };
+
+    let sequence = fields.get("Sequence").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if sequence != 0 && sequence < account_root.sequence {
+        return Ok(failed(
+            TransactionResult::tefPAST_SEQ,
+            "This sequence number has already passed",
+        ));
+    }
+    if sequence > account_root.sequence {
+        return Ok(failed(
+            TransactionResult::terPRE_SEQ,
+            "Missing/inapplicable prior transaction",
+        ));
+    }
+
+    let fee = drops_field(&fields, "Fee")?;
+    let balance = account_root
+        .balance
+        .as_deref()
+        .and_then(|balance| balance.parse::<u64>().ok())
+        .ok_or_else(|| Error::validation(format!("Account {account} has no XRP balance")))?;
+    if balance < fee {
+        return Ok(failed(
+            TransactionResult::terINSUF_FEE_B,
+            "Account balance can't pay fee",
+        ));
+    }
+
+    let mut balance_changes = Vec::new();
+    push_change(&mut balance_changes, account, "XRP", None, -Decimal::from_drops(fee));
+
+    if string_field(&fields, "TransactionType")? == "Payment" {
+        let reserves = client_service.get_reserves().await?;
+        let spendable = balance
+            .saturating_sub(fee)
+            .saturating_sub(reserves.for_owner_count(account_root.owner_count));
+        let destination = string_field(&fields, "Destination")?;
+        let amount = fields
+            .get("Amount")
+            .ok_or_else(|| Error::validation("Payment has no Amount"))?;
+        let send_max = fields.get("SendMax").unwrap_or(amount);
+
+        // Funding of what the account sends
+        match codec::amount_value(send_max)? {
+            (None, value) if value > Decimal::from_drops(spendable) => {
+                return Ok(failed(
+                    TransactionResult::tecUNFUNDED_PAYMENT,
+                    "Insufficient XRP balance to send",
+                ));
+            }
+            (Some((currency, issuer)), value) if issuer != account => {
+                let held = trust_line_balance(client_service, account, &currency, &issuer).await?;
+                if held.is_none_or(|held| held < value) {
+                    return Ok(failed(
+                        TransactionResult::tecUNFUNDED_PAYMENT,
+                        "Insufficient balance to send",
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        // The destination must be able to receive what is delivered
+        let delivered = codec::amount_value(amount)?;
+        match &delivered {
+            (None, value)
+                if *value < Decimal::from_drops(reserves.base)
+                    && !client_service.account_exists(destination).await? =>
+            {
+                return Ok(failed(
+                    TransactionResult::tecNO_DST_INSUF_XRP,
+                    "Destination does not exist. Too little XRP sent to create it",
+                ));
+            }
+            (Some((currency, issuer)), _) if issuer != destination => {
+                if !client_service.account_exists(destination).await? {
+                    return Ok(failed(
+                        TransactionResult::tecNO_DST,
+                        "Destination does not exist",
+                    ));
+                }
+                let line =
+                    trust_line_balance(client_service, destination, currency, issuer).await?;
+                if line.is_none() {
+                    return Ok(failed(
+                        TransactionResult::tecPATH_DRY,
+                        "Path could not send partial amount",
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        // Only a direct payment delivers exactly its amount
+        let flags = fields.get("Flags").and_then(Value::as_u64).unwrap_or(0);
+        let direct = !fields.contains_key("SendMax") && flags & TF_PARTIAL_PAYMENT == 0;
+        if direct && destination != account {
+            let (currency, issuer, value) = match delivered {
+                (None, value) => ("XRP".to_string(), None, value),
+                (Some((currency, issuer)), value) => (currency, Some(issuer), value),
+            };
+            let issuer = issuer.as_deref();
+            push_change(&mut balance_changes, account, &currency, issuer, -value.clone());
+            push_change(&mut balance_changes, destination, &currency, issuer, value);
+        }
+    }
+
+    Ok(Simulation {
+        result: TransactionResult::tesSUCCESS,
+        message: "Preflight passed; paths and offers were not checked".to_string(),
+        source: SimulationSource::Preflight,
+        balance_changes,
+    })
+}
+
+fn failed(result: TransactionResult, message: &str) -> Simulation {
+    Simulation {
+        result,
+        message: message.to_string(),
+        source: SimulationSource::Preflight,
+        balance_changes: Vec::new(),
+    }
+}
+
+/// Balance `account` holds on its trust line for `currency` issued by `issuer`, or `None`
+/// without such a trust line
+async fn trust_line_balance(
+    client_service: &ClientService,
+    account: &str,
+    currency: &str,
+    issuer: &str,
+) -> Result<Option<Decimal>, Error> {
+    let lines = client_service.get_account_lines(account).await?;
+
+    lines
+        .lines
+        .iter()
+        .find(|line| line.account == issuer && line.currency == currency)
+        .map(|line| line.balance.parse::<Decimal>())
+        .transpose()
+}
+
+fn drops_field(fields: &Map<String, Value>, name: &str) -> Result<u64, Error> {
+    string_field(fields, name)?
+        .parse::<u64>()
+        .map_err(|e| Error::validation(format!("Invalid {name}: {e}")))
+}
@@ -0,0 +1,164 @@
+//! Identity of an asset on the ledger: XRP, or a token given by currency code and issuer.
+
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::{fmt, str::FromStr};
+use xrpl_types::{Amount, CurrencyCode, IssuedAmount};
+
+use super::{address, amount::Decimal, client_service::ClientService, error::Error};
+
+/// XRP, or a token identified by its currency code and issuer.
+///
+/// Written as `XRP` or `CURRENCY.ISSUER`, e.g. `USD.rhub8VRN55s94qWKDv6jmDy1pUykJzF3wq`,
+/// where the currency is a three letter code or 40 hex characters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub enum Token {
+    Xrp,
+    Issued {
+        currency: String,
+        /// Classic address of the issuer
+        issuer: String,
+    },
+}
+
+impl Token {
+    /// A token of `issuer`, which may be given as an X-address
+    pub fn issued(currency: &str, issuer: &str) -> Result<Self, Error> {
+        CurrencyCode::from_str(currency)
+            .map_err(|e| Error::validation(format!("Invalid currency code {currency}: {e}")))?;
+        if currency == "XRP" {
+            return Err(Error::validation("XRP is not an issued currency"));
+        }
+
+        Ok(Self::Issued {
+            currency: currency.to_string(),
+            issuer: address::to_classic_address(issuer)?,
+        })
+    }
+
+    /// The only token `issuer` issues, for callers that know just the issuer. Fails if it
+    /// issues none or several, which then have to be told apart by currency.
+    pub async fn from_issuer(client_service: &ClientService, issuer: &str) -> Result<Self, Error> {
+        let currencies = client_service.get_account_currencies(issuer).await?;
+
+        let mut codes: Vec<&String> = currencies
+            .send_currencies
+            .iter()
+            .chain(&currencies.receive_currencies)
+            .collect();
+        codes.sort();
+        codes.dedup();
+
+        match codes.as_slice() {
+            [currency] => Self::issued(currency, issuer),
+            [] => Err(Error::validation(format!("No currencies found for issuer {issuer}"))),
+            _ => Err(Error::validation(format!(
+                "Issuer {issuer} has several currencies ({}), use CURRENCY.ISSUER",
+                codes
+                    .iter()
+                    .map(|code| code.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    /// Parse `XRP` or `CURRENCY.ISSUER`, or look up the currency of a bare issuer address
+    /// with [`Self::from_issuer`]
+    pub async fn resolve(client_service: &ClientService, token: &str) -> Result<Self, Error> {
+        if token != "XRP" && !token.contains('.') {
+            return Self::from_issuer(client_service, token).await;
+        }
+
+        token.parse()
+    }
+
+    /// The token of an amount from an API response
+    pub fn of_amount(amount: &xrpl_http_client::Amount) -> Self {
+        match amount {
+            xrpl_http_client::Amount::Drops(_) => Self::Xrp,
+            xrpl_http_client::Amount::Issued(issued) => Self::Issued {
+                currency: issued.currency.clone(),
+                issuer: issued.issuer.clone(),
+            },
+        }
+    }
+
+    pub fn is_xrp(&self) -> bool {
+        matches!(self, Self::Xrp)
+    }
+
+    /// `XRP` or the currency code of the token
+    pub fn currency(&self) -> &str {
+        match self {
+            Self::Xrp => "XRP",
+            Self::Issued { currency, .. } => currency,
+        }
+    }
+
+    /// The issuer, `None` for XRP
+    pub fn issuer(&self) -> Option<&str> {
+        match self {
+            Self::Xrp => None,
+            Self::Issued { issuer, .. } => Some(issuer),
+        }
+    }
+
+    /// `value` of this token, in XRP rather than drops for XRP
+    pub fn amount(&self, value: &Decimal) -> Result<Amount, Error> {
+        match self {
+            Self::Xrp => Ok(Amount::Drops(value.to_drops_amount()?)),
+            Self::Issued { .. } => Ok(Amount::Issued(self.issued_amount(value)?)),
+        }
+    }
+
+    /// `value` of this token in the form API requests take, e.g. for `ripple_path_find`
+    pub fn api_amount(&self, value: &Decimal) -> Result<xrpl_http_client::Amount, Error> {
+        match self {
+            Self::Xrp => Ok(xrpl_http_client::Amount::drops(value.to_drops()?)),
+            Self::Issued { currency, issuer } => Ok(xrpl_http_client::Amount::issued(
+                value.to_string(),
+                currency.as_str(),
+                issuer.as_str(),
+            )),
+        }
+    }
+
+    /// `value` of this token as an issued amount, e.g. for a trust line limit
+    pub fn issued_amount(&self, value: &Decimal) -> Result<IssuedAmount, Error> {
+        let Self::Issued { currency, issuer } = self else {
+            return Err(Error::validation("XRP is not an issued currency"));
+        };
+
+        let currency = CurrencyCode::from_str(currency)
+            .map_err(|e| Error::validation(format!("Invalid currency code: {e}")))?;
+        let issuer = address::decode_account_id(issuer)?;
+
+        IssuedAmount::from_issued_value(value.to_issued_value()?, currency, issuer)
+            .map_err(|e| Error::validation(format!("Failed to create issued amount: {e}")))
+    }
+}
+
+impl FromStr for Token {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "XRP" {
+            return Ok(Self::Xrp);
+        }
+
+        let (currency, issuer) = s.split_once('.').ok_or_else(|| {
+            Error::validation(format!("Invalid token {s}, expected XRP or CURRENCY.ISSUER"))
+        })?;
+
+        Self::issued(currency, issuer)
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xrp => write!(f, "XRP"),
+            Self::Issued { currency, issuer } => write!(f, "{currency}.{issuer}"),
+        }
+    }
+}
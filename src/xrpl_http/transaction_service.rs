@@ -1,252 +1,1321 @@
-use std::str::FromStr;
-use bigdecimal::{FromPrimitive, ToPrimitive, BigDecimal};
-use tracing::info;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use futures::{StreamExt, future, stream};
+use tracing::{info, warn};
 use xrpl_binary_codec::serialize;
-use xrpl_http_client::{Client, SubmitRequest, SubmitResponse};
+use xrpl_http_client::{
+    Client, ResultCategory, ServerInfoRequest, SubmitRequest, SubmitResponse, TransactionResult,
+};
 use xrpl_types::{
-    AccountId, Amount, CurrencyCode, DropsAmount, IssuedAmount, IssuedValue, PaymentFlags, PaymentTransaction, Transaction, TrustSetTransaction
+    AccountId, Amount, Blob, DropsAmount, PaymentFlags, PaymentTransaction, Transaction,
+    TransactionCommon, TrustSetTransaction,
+};
+
+use super::{
+    address,
+    amount::{Decimal, XrpAmount},
+    batch::{self, BatchOptions, PayoutRecord, PayoutRow, PayoutStatus, ResultsFile},
+    client_service::ClientService,
+    codec,
+    error::Error,
+    fee::FeeStrategy,
+    mnemonic::DerivationPath,
+    multisign::{self, PartialSignature},
+    paths::{PathAlternative, PathSet, SourceCurrency},
+    rpc::{RipplePathFindRequest, SubmitMultisignedRequest, SubmitMultisignedResponse},
+    secret::SecretString,
+    sequence::SequenceManager,
+    signer::{self, KeyType, RippleSigner, Signer},
+    simulate,
+    token::Token,
+    transactions::{
+        ASF_DISABLE_MASTER, AccountSetTransaction, MAX_SIGNER_ENTRIES, MAX_TICKETS,
+        SetRegularKeyTransaction, SignerListSetTransaction, TicketCreateTransaction, WithMemos,
+    },
+    types::{
+        OfflineSigningParams, PaymentOptions, RegularKeyRotation, SignedTransaction, SignerEntry,
+        Simulation, SimulationSource, SwapError, SwapRequest, TransactionOutcome,
+    },
+    verify::{self, ExpectedPayment, VerifiedTransaction},
 };
 
-use super::{client_service::ClientService, signer::RippleSigner, types::SwapRequest};
+/// How often `submit_and_wait` checks for validation, about once per ledger close
+const POLL_INTERVAL: Duration = Duration::from_secs(4);
+
+/// Ledgers after the current open ledger a submitted transaction has to get validated
+const SUBMIT_LEDGER_WINDOW: u32 = 4;
+
+/// Ledgers a ticketed transaction stays valid for by default, about an hour
+pub const PRESIGNED_LEDGER_WINDOW: u32 = 900;
 
 /// Service for transaction operations that require signing and submission
 pub struct TransactionService {
     client: Client,
     client_service: ClientService,
-    signer: RippleSigner,
+    signer: Box<dyn Signer>,
+    sequences: Arc<SequenceManager>,
+    fee_strategy: Arc<FeeStrategy>,
 }
 
 impl TransactionService {
-    /// Create a new transaction service from a seed string
-    pub fn from_seed(seed_str: &str) -> Result<Self, String> {
-        let signer = RippleSigner::from_seed(seed_str)?;
-        let client = Client::new();
-        let client_service = ClientService::new();
-
-        Ok(Self {
-            client,
-            client_service,
-            signer,
-        })
+    /// Create a new transaction service from a family seed
+    pub fn from_seed(seed: &SecretString) -> Result<Self, Error> {
+        let signer = RippleSigner::from_seed(seed)?;
+
+        Ok(Self::new(signer))
+    }
+
+    /// Create a new transaction service from a BIP39 mnemonic at the given BIP44 path
+    pub fn from_mnemonic(
+        phrase: &SecretString,
+        passphrase: Option<&SecretString>,
+        path: DerivationPath,
+    ) -> Result<Self, Error> {
+        let signer = RippleSigner::from_mnemonic(phrase, passphrase, path)?;
+
+        Ok(Self::new(signer))
     }
 
     /// Create a new transaction service with an existing signer
-    pub fn new(signer: RippleSigner) -> Self {
+    pub fn new(signer: impl Signer + 'static) -> Self {
         Self {
             client: Client::new(),
             client_service: ClientService::new(),
-            signer,
+            signer: Box::new(signer),
+            sequences: Arc::new(SequenceManager::new()),
+            fee_strategy: Arc::new(FeeStrategy::default()),
         }
     }
 
+    /// Share sequence allocation with other services submitting for the same accounts,
+    /// e.g. one signing with the master key and one with a regular key
+    pub fn with_sequence_manager(mut self, sequences: Arc<SequenceManager>) -> Self {
+        self.sequences = sequences;
+        self
+    }
+
+    /// Pick fees with `fee_strategy` instead of the default urgent policy and caps; share
+    /// it with other services to enforce one hourly budget across them
+    pub fn with_fee_strategy(mut self, fee_strategy: Arc<FeeStrategy>) -> Self {
+        self.fee_strategy = fee_strategy;
+        self
+    }
+
+    /// Get the fee strategy, e.g. to report the fees spent over the last hour
+    pub fn fee_strategy(&self) -> &FeeStrategy {
+        &self.fee_strategy
+    }
+
+    /// Forget the locally allocated sequences of this account and read it from the
+    /// ledger again, e.g. after discarding blobs from [`Self::prepare_transaction`]
+    pub fn reset_sequence(&self) {
+        self.sequences.reset(self.signer.address());
+    }
+
     /// Get the account address
     pub fn address(&self) -> &str {
         self.signer.address()
     }
 
+    /// Get the algorithm of the signing key
+    pub fn key_type(&self) -> KeyType {
+        self.signer.key_type()
+    }
+
     /// Get a reference to the client service for read-only operations
     pub fn client_service(&self) -> &ClientService {
         &self.client_service
     }
     
-    pub async fn send_token_as_bytes(&self, token_address: &str, amount: &str, destination_address: &str) -> Result<Vec<u8>, String> {
+    pub async fn send_token_as_bytes(
+        &self,
+        token: &Token,
+        amount: &str,
+        destination_address: &str,
+        options: &PaymentOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let payment = self.token_payment(token, amount, destination_address, options)?;
+        self.prepare_transaction(payment).await
+    }
+
+    /// Like [`Self::send_token_as_bytes`], but signed with a ticket so that several such
+    /// payments can be submitted later in any order
+    pub async fn send_token_as_bytes_with_ticket(
+        &self,
+        token: &Token,
+        amount: &str,
+        destination_address: &str,
+        options: &PaymentOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let payment = self.token_payment(token, amount, destination_address, options)?;
+        self.prepare_ticketed_transaction(payment).await
+    }
+
+    fn token_payment(
+        &self,
+        token: &Token,
+        amount: &str,
+        destination_address: &str,
+        options: &PaymentOptions,
+    ) -> Result<WithMemos<PaymentTransaction>, Error> {
         let account_id = AccountId::from_address(self.signer.address())
-            .map_err(|e| format!("Invalid account address: {e}"))?;
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
         
-        let destination = AccountId::from_address(destination_address).unwrap();
+        let (destination, destination_tag) =
+            address::resolve_destination(destination_address, options.destination_tag)?;
         
-        let currencies = self.client_service
-            .get_account_currencies(token_address)
-            .await?;
-
-        if currencies.receive_currencies.is_empty() {
-            return Err(format!("No currencies found for token: {}", token_address));
-        }
-
-        let currency_code = &currencies.receive_currencies[0];
-        let currency = CurrencyCode::from_str(currency_code)
-            .map_err(|e| format!("Invalid currency code: {e}"))?;
+        let amount = token.amount(&amount.parse::<Decimal>()?)?;
         
-        let value = BigDecimal::from_str(amount)
-            .map_err(|e| format!("Invalid amount format: {e}"))?;
-
-        let (value_big_int, scale) = value.into_bigint_and_scale();
+        let mut payment = PaymentTransaction::new(account_id, amount, destination);
+        payment.destination_tag = destination_tag;
+        
+        options.apply(payment)
+    }
 
-        let mantissa = value_big_int
-            .to_i64()
-            .ok_or("Amount too large for mantissa conversion")?;
+    /// Sign a payment of `amount` XRP to `destination_address` without submitting it
+    pub async fn send_xrp_as_bytes(
+        &self,
+        amount: &XrpAmount,
+        destination_address: &str,
+        options: &PaymentOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let payment = self.xrp_payment(amount, destination_address, options)?;
+        self.prepare_transaction(payment).await
+    }
 
-        let exponent = -(scale as i8);
+    /// Pay `amount` XRP to `destination_address` and wait for the final outcome
+    pub async fn send_xrp(
+        &self,
+        amount: &XrpAmount,
+        destination_address: &str,
+        options: &PaymentOptions,
+    ) -> Result<TransactionOutcome, Error> {
+        let payment = self.xrp_payment(amount, destination_address, options)?;
 
         info!(
-            "Parsed amount - mantissa: {}, exponent: {}",
-            mantissa, exponent
+            "Paying {} from {} to {}",
+            amount,
+            self.signer.address(),
+            destination_address
         );
 
-        let issued_value = IssuedValue::from_mantissa_exponent(mantissa, exponent)
-            .map_err(|e| format!("Failed to create issued value: {e}"))?;
-        
-        let issuer = AccountId::from_address(token_address).unwrap();
-        let amount = Amount::Issued(
-            IssuedAmount::from_issued_value(issued_value, currency, issuer).unwrap()
-        );
-        
-        let payment = PaymentTransaction::new(account_id, amount, destination);
-        
-        self.prepare_transaction(payment).await
+        self.submit_and_wait(payment).await
+    }
+
+    fn xrp_payment(
+        &self,
+        amount: &XrpAmount,
+        destination_address: &str,
+        options: &PaymentOptions,
+    ) -> Result<WithMemos<PaymentTransaction>, Error> {
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+
+        let (destination, destination_tag) =
+            address::resolve_destination(destination_address, options.destination_tag)?;
+        if destination.0 == account_id.0 {
+            return Err(Error::validation("Cannot pay XRP to the sending account"));
+        }
+
+        let drops = amount.to_drops_amount()?;
+        if drops.drops() == 0 {
+            return Err(Error::validation("XRP payment amount must be positive"));
+        }
+
+        let mut payment = PaymentTransaction::new(account_id, Amount::Drops(drops), destination);
+        payment.destination_tag = destination_tag;
+
+        options.apply(payment)
     }
 
     
-    pub async fn send_transaction_from_bytes(&self, tx_bytes: Vec<u8>) -> Result<SubmitResponse, String> {
+    pub async fn send_transaction_from_bytes(&self, tx_bytes: Vec<u8>) -> Result<SubmitResponse, Error> {
         let req = SubmitRequest::new(hex::encode(&tx_bytes));
         let response = self
             .client
             .call(req)
             .await
-            .map_err(|e| format!("Failed to submit transaction: {e}"))?;
+            .map_err(|e| Error::client("Failed to submit transaction", e))?;
 
         Ok(response)
     }
     
-    /// Execute a swap transaction
-    pub async fn swap(&self, request: SwapRequest) -> Result<SubmitResponse, String> {
+    /// Verify a signed blob from another party, e.g. the output of [`Self::send_token_as_bytes`]
+    pub fn verify_transaction_bytes(&self, tx_bytes: &[u8]) -> Result<VerifiedTransaction, Error> {
+        verify::verify_transaction(tx_bytes)
+    }
+
+    /// Submit a payment blob from another party only once it is verified to pay `expected`,
+    /// and wait for its final outcome
+    pub async fn send_payment_from_bytes(
+        &self,
+        tx_bytes: Vec<u8>,
+        expected: &ExpectedPayment,
+    ) -> Result<TransactionOutcome, Error> {
+        let verified = verify::verify_transaction(&tx_bytes)?;
+        verified.check_payment(expected)?;
+
+        info!(
+            "Verified payment {} from {} to {}",
+            verified.hash, verified.account, expected.destination
+        );
+
+        self.submit_blob_and_wait(tx_bytes).await
+    }
+
+    /// Pay every row of a payout file from this account, see [`super::batch`].
+    ///
+    /// All rows are validated before anything is signed. Each payment is recorded in the
+    /// results file at `results_path` before it is submitted and again once it is final, so
+    /// running the same batch with the same results file resumes it: final rows are skipped,
+    /// pending ones are resubmitted as signed and expired ones are paid again. Stops signing
+    /// new payments at the first one that cannot be prepared, e.g. when the fee budget is
+    /// spent, and returns that error once the submitted ones are final.
+    ///
+    /// Returns the latest record of every row paid so far; a payment whose submission
+    /// failed stays pending until the batch is resumed.
+    pub async fn pay_batch(
+        &self,
+        payouts: &[PayoutRow],
+        results_path: &Path,
+        options: &BatchOptions,
+    ) -> Result<Vec<PayoutRecord>, Error> {
         let account_id = AccountId::from_address(self.signer.address())
-            .map_err(|e| format!("Invalid account address: {e}"))?;
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+
+        let mut payments = Vec::with_capacity(payouts.len());
+        let mut invalid = Vec::new();
+        for (row, payout) in payouts.iter().enumerate() {
+            match payout.payment(account_id) {
+                Ok(payment) => payments.push(payment),
+                Err(e) => invalid.push(format!("row {row}: {e}")),
+            }
+        }
+        if !invalid.is_empty() {
+            return Err(Error::validation(format!(
+                "Invalid payouts: {}",
+                invalid.join("; ")
+            )));
+        }
+
+        let (results, mut latest) = ResultsFile::open(results_path)?;
+        if let Some((row, _)) = payouts
+            .iter()
+            .enumerate()
+            .find(|(row, payout)| latest.get(row).is_some_and(|record| !record.matches(payout)))
+        {
+            return Err(Error::validation(format!(
+                "Row {row} differs from its entry in {}, the payout file changed",
+                results_path.display()
+            )));
+        }
+        let results = Mutex::new(results);
+        let max_in_flight = options.max_in_flight.max(1);
+
+        // Settle what a previous run left pending before signing anything new, which
+        // could otherwise take the same sequences
+        let pending: Vec<PayoutRecord> = latest
+            .values()
+            .filter(|record| record.status == PayoutStatus::Pending)
+            .cloned()
+            .collect();
+        if !pending.is_empty() {
+            info!("Resuming {} pending payouts", pending.len());
+        }
+        let mut settled = stream::iter(pending)
+            .map(|record| self.settle_payout(record))
+            .buffer_unordered(max_in_flight);
+        while let Some(record) = settled.next().await {
+            if record.status != PayoutStatus::Pending {
+                results.lock().unwrap().append(&record)?;
+            }
+            latest.insert(record.row, record);
+        }
+        drop(settled);
 
-        let amount = request.get_max_amount_out().await.unwrap();
+        let to_pay: Vec<_> = payouts
+            .iter()
+            .zip(payments)
+            .enumerate()
+            .filter(|(row, _)| batch::needs_payment(latest.get(row)))
+            .collect();
+        info!(
+            "Paying {} of {} payouts from {}",
+            to_pay.len(),
+            payouts.len(),
+            self.signer.address()
+        );
+
+        // Sign one payment per `submit_interval` while up to `max_in_flight` are awaited
+        let stopped = Mutex::new(None);
+        let settled = stream::iter(to_pay.into_iter().enumerate())
+            .then(|(i, (row, (payout, payment)))| {
+                let (results, stopped) = (&results, &stopped);
+                async move {
+                    if stopped.lock().unwrap().is_some() {
+                        return None;
+                    }
+                    if i > 0 {
+                        tokio::time::sleep(options.submit_interval).await;
+                    }
+
+                    match self.sign_payout(row, payout, payment, results).await {
+                        Ok(record) => Some(record),
+                        Err(e) => {
+                            warn!("Stopping batch at row {row}: {e}");
+                            *stopped.lock().unwrap() = Some(e);
+                            None
+                        }
+                    }
+                }
+            })
+            .filter_map(future::ready)
+            .map(|record| self.settle_payout(record))
+            .buffer_unordered(max_in_flight);
+        // The sleeping sign step is not `Unpin`, and the stream borrows `stopped` until
+        // it is dropped at the end of this block
+        {
+            futures::pin_mut!(settled);
+            while let Some(record) = settled.next().await {
+                if record.status != PayoutStatus::Pending {
+                    results.lock().unwrap().append(&record)?;
+                }
+                latest.insert(record.row, record);
+            }
+        }
+
+        if let Some(e) = stopped.into_inner().unwrap() {
+            return Err(e);
+        }
+
+        let mut records: Vec<PayoutRecord> = latest
+            .into_values()
+            .filter(|record| record.row < payouts.len())
+            .collect();
+        records.sort_by_key(|record| record.row);
+
+        Ok(records)
+    }
+
+    /// Sign the payment of `row` and record it as pending before it is submitted
+    async fn sign_payout(
+        &self,
+        row: usize,
+        payout: &PayoutRow,
+        payment: WithMemos<PaymentTransaction>,
+        results: &Mutex<ResultsFile>,
+    ) -> Result<PayoutRecord, Error> {
+        let tx_blob = self.prepare_transaction(payment).await?;
+        let hash = signer::transaction_hash(&tx_blob);
+        let record = PayoutRecord::pending(row, payout, hash, &tx_blob);
+        results.lock().unwrap().append(&record)?;
+
+        Ok(record)
+    }
+
+    /// Submit the blob of a pending payout and wait for its outcome. The record stays
+    /// pending if that fails, so that resuming the batch submits the same blob again.
+    async fn settle_payout(&self, record: PayoutRecord) -> PayoutRecord {
+        let outcome = match hex::decode(record.tx_blob.as_deref().unwrap_or_default()) {
+            Ok(tx_blob) => self.submit_blob_and_wait(tx_blob).await,
+            Err(e) => Err(Error::validation(format!("Invalid pending transaction: {e}"))),
+        };
+
+        match outcome {
+            Ok(outcome) => {
+                info!("Payout of row {} to {}: {}", record.row, record.destination, outcome);
+                record.settle(&outcome)
+            }
+            Err(e) => {
+                warn!("Payout of row {} ({}) is still pending: {e}", record.row, record.hash);
+                record
+            }
+        }
+    }
+
+    /// Execute a swap transaction, with `options` e.g. to put an order id in a memo.
+    ///
+    /// Only a successful swap is `Ok`; any other outcome is classified as a [`SwapError`].
+    pub async fn swap(
+        &self,
+        request: SwapRequest,
+        options: &PaymentOptions,
+    ) -> Result<TransactionOutcome, SwapError> {
+        let (payment, paths) = self.swap_payment(&request, options).await?;
+
+        let tx_blob = self
+            .prepare_transaction_with(self.signer.as_ref(), payment, &paths)
+            .await?;
+        let outcome = self.submit_blob_and_wait(tx_blob).await?;
+        match outcome.error() {
+            None => Ok(outcome),
+            Some(error) => Err(error.into()),
+        }
+    }
+
+    /// Simulate a swap without committing funds, e.g. to find out beforehand whether it
+    /// would end in `tecPATH_PARTIAL` or `tecUNFUNDED_PAYMENT`
+    pub async fn simulate_swap(
+        &self,
+        request: &SwapRequest,
+        options: &PaymentOptions,
+    ) -> Result<Simulation, SwapError> {
+        let (payment, paths) = self.swap_payment(request, options).await?;
+
+        Ok(self.simulate_with_paths(payment, &paths).await?)
+    }
+
+    /// Ways to receive `amount_out_min` of a swap's token out for its token in, cheapest
+    /// first, from `ripple_path_find`. An empty path set means the default path, e.g.
+    /// the direct order book.
+    pub async fn find_swap_paths(
+        &self,
+        request: &SwapRequest,
+    ) -> Result<Vec<PathAlternative>, SwapError> {
+        request.validate()?;
+
+        let amount_out_min = request.amount_out_min.parse::<Decimal>()?;
+        let mut path_find = RipplePathFindRequest::new(
+            self.signer.address(),
+            self.signer.address(),
+            request.token_out.api_amount(&amount_out_min)?,
+        );
+        path_find.source_currencies = vec![SourceCurrency::from(&request.token_in)];
+
+        let response = self.client_service.find_paths(path_find).await?;
+
+        let mut alternatives = Vec::new();
+        for alternative in response.alternatives {
+            if let Some(source_value) = alternative.source_value(&request.token_in)? {
+                alternatives.push((source_value, alternative));
+            }
+        }
+        alternatives.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        Ok(alternatives
+            .into_iter()
+            .map(|(_, alternative)| alternative)
+            .collect())
+    }
+
+    /// The self-payment of a swap and the paths of the cheapest alternative, which has to
+    /// cost at most `amount_in`
+    async fn swap_payment(
+        &self,
+        request: &SwapRequest,
+        options: &PaymentOptions,
+    ) -> Result<(WithMemos<PaymentTransaction>, PathSet), SwapError> {
+        let alternatives = self.find_swap_paths(request).await?;
+        let no_path = || {
+            SwapError::NoLiquidity(format!(
+                "No path delivers {} {} for {} {}",
+                request.amount_out_min, request.token_out, request.amount_in, request.token_in
+            ))
+        };
+        let best = alternatives.into_iter().next().ok_or_else(no_path)?;
+        let cost = best.source_value(&request.token_in)?.ok_or_else(no_path)?;
+        if cost > request.amount_in.parse::<Decimal>()? {
+            return Err(SwapError::NoLiquidity(format!(
+                "Best path needs {cost} {} to deliver {} {}, more than {}",
+                request.token_in, request.amount_out_min, request.token_out, request.amount_in
+            )));
+        }
+        info!(
+            "Swapping {} for {} along {} paths, expected to cost {cost}",
+            request.token_in,
+            request.token_out,
+            best.paths_computed.0.len()
+        );
+
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| SwapError::InvalidSwap(format!("Invalid account address: {e}")))?;
+
+        let amount = request.get_max_amount_out()?;
 
         // Create payment transaction
         let destination = account_id; // Self-payment for swaps
         let mut payment = PaymentTransaction::new(account_id, amount, destination);
 
-        let deliver_min = request.get_receive_min().await.unwrap();
-        let send_max = request.get_send_max().await.unwrap();
+        let deliver_min = request.get_receive_min()?;
+        let send_max = request.get_send_max()?;
         payment.deliver_min = Some(deliver_min);
         payment.send_max = Some(send_max);
-        payment.common.fee = Some(DropsAmount::from_drops(12).unwrap());
         payment.flags = PaymentFlags::PartialPayment.into();
 
-        self.prepare_and_submit_transaction(payment).await
+        Ok((options.apply(payment)?, best.paths_computed))
     }
 
     /// Create a trust line for a token
     pub async fn create_trust_line(
         &self,
-        token_address: &str,
+        token: &Token,
         limit: Option<&str>,
-    ) -> Result<SubmitResponse, String> {
-        let currencies = self
-            .client_service
-            .get_account_currencies(token_address)
-            .await?;
+    ) -> Result<TransactionOutcome, Error> {
+        info!("Signer address: {}", self.signer.address());
+        
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
 
-        if currencies.receive_currencies.is_empty() {
-            return Err("No currencies found for the given address".to_string());
-        }
+        let limit_value = limit.unwrap_or("10000000").parse::<Decimal>()?;
+        let issued_amount = token.issued_amount(&limit_value)?;
 
-        let currency_code = &currencies.receive_currencies[0];
-        
-        info!("Signer address: {}", self.signer.address);
-        info!("Signer address v2: {}", self.signer.address());
-        
-        let account_id = AccountId::from_address(&self.signer.address.clone())
-            .map_err(|e| format!("Invalid account address: {e}"))?;
+        let tx = TrustSetTransaction::new(account_id, issued_amount);
 
-        let limit_value = limit.unwrap_or("10000000");
-        let limit_value = limit_value.parse::<i64>().unwrap();
-        let issued_value = IssuedValue::from_mantissa_exponent(limit_value, 0).unwrap();
+        self.submit_and_wait(tx).await
+    }
+    
+    
+    pub async fn prepare_transaction<T>(&self, transaction: T) -> Result<Vec<u8>, Error> 
+    where
+        T: Transaction + Clone + std::fmt::Debug,
+    {
+        self.prepare_transaction_with(self.signer.as_ref(), transaction, &PathSet::default())
+            .await
+    }
 
-        let currency = CurrencyCode::from_str(currency_code)
-            .map_err(|e| format!("Invalid currency code: {e}"))?;
+    /// Autofill `transaction` without signing or submitting it and report its expected
+    /// engine result and balance changes.
+    ///
+    /// Uses the server's `simulate` method, falling back to a local preflight of the
+    /// account, sequence, fee and funding when the server does not support it.
+    pub async fn simulate<T>(&self, transaction: T) -> Result<Simulation, Error>
+    where
+        T: Transaction,
+    {
+        self.simulate_with_paths(transaction, &PathSet::default()).await
+    }
 
-        let issuer = AccountId::from_address(token_address)
-            .map_err(|e| format!("Invalid token address: {e}"))?;
+    /// [`Self::simulate`] for a payment taking `paths`
+    async fn simulate_with_paths<T>(
+        &self,
+        mut transaction: T,
+        paths: &PathSet,
+    ) -> Result<Simulation, Error>
+    where
+        T: Transaction,
+    {
+        let common_mut = transaction.common_mut();
+        if common_mut.sequence.is_none() {
+            let account = common_mut.account.to_address();
+            let resp = self.client_service.get_account_info(&account).await?;
+            common_mut.sequence = Some(resp.account_data.sequence);
+        }
+        self.autofill_fee(common_mut, SUBMIT_LEDGER_WINDOW).await?;
+        common_mut.signing_pub_key = Some(Blob(Vec::new()));
+        common_mut.txn_signature = None;
 
-        let issued_amount = IssuedAmount::from_issued_value(issued_value, currency, issuer)
-            .map_err(|e| format!("Failed to create issued amount: {e}"))?;
+        let mut tx_blob = serialize::serialize(&transaction)
+            .map_err(|e| Error::validation(format!("Failed to serialize transaction: {e}")))?;
+        tx_blob.extend(paths.encode()?);
 
-        let mut tx = TrustSetTransaction::new(account_id, issued_amount);
+        let simulation = match self.client_service.simulate(&tx_blob).await? {
+            Some(response) => Simulation {
+                result: response.engine_result,
+                message: response.engine_result_message,
+                source: SimulationSource::Server,
+                balance_changes: response
+                    .meta
+                    .as_ref()
+                    .map(simulate::balance_changes)
+                    .unwrap_or_default(),
+            },
+            None => simulate::preflight(&self.client_service, &tx_blob).await?,
+        };
 
-        let address = self.signer.address.clone();
-        let resp = self.client_service.get_account_info(&address).await?;
-        
-        let common_mut = tx.common_mut();
-        common_mut.sequence = Some(resp.account_data.sequence);
-        
-        self.client
-            .prepare_transaction(common_mut)
-            .await
-            .map_err(|e| format!("Failed to prepare transaction: {e}"))
-            .unwrap();
-        
-        self.signer.sign_transaction(&mut tx)?;
-        
-        let tx_bytes = serialize::serialize(&tx)
-            .map_err(|e| format!("Failed to serialize transaction: {e}"))?;
-        
-        let req = SubmitRequest::new(hex::encode(&tx_bytes));
-        let response = self
-            .client
-            .call(req)
-            .await
-            .map_err(|e| format!("Failed to submit transaction: {e}"))?;
+        info!(
+            "Simulated {} with {:?}: {}",
+            transaction.common().account.to_address(),
+            simulation.result,
+            simulation.message
+        );
 
-        Ok(response)
+        Ok(simulation)
     }
-    
-    
-    pub async fn prepare_transaction<T>(&self, mut transaction: T) -> Result<Vec<u8>, String> 
+
+    /// Sign `transaction` with caller supplied sequence (or ticket), fee and
+    /// `LastLedgerSequence`, for machines without network access
+    pub async fn sign_offline<T>(
+        &self,
+        transaction: T,
+        params: OfflineSigningParams,
+    ) -> Result<SignedTransaction, Error>
+    where
+        T: Transaction,
+    {
+        signer::sign_offline(self.signer.as_ref(), transaction, params).await
+    }
+
+    /// Sign `transaction` with one of this account's tickets instead of its next sequence,
+    /// so it does not depend on any other transaction and can be submitted in any order.
+    ///
+    /// Unless already set, the fee follows the fee strategy and `LastLedgerSequence` leaves
+    /// [`PRESIGNED_LEDGER_WINDOW`] ledgers to submit it.
+    pub async fn prepare_ticketed_transaction<T>(&self, mut transaction: T) -> Result<Vec<u8>, Error>
     where
         T: Transaction + Clone + std::fmt::Debug,
     {
-        let address = self.signer.address.clone();
-        let resp = self.client_service.get_account_info(&address).await?;
-        
+        let account = transaction.common().account.to_address();
+        let ticket = self.take_ticket(&account).await?;
+
         let common_mut = transaction.common_mut();
-        common_mut.sequence = Some(resp.account_data.sequence);
-        
-        self.client
-            .prepare_transaction(common_mut)
-            .await
-            .map_err(|e| format!("Failed to prepare transaction: {e}"))
-            .unwrap();
+        common_mut.sequence = Some(0);
+        common_mut.ticket_sequence = Some(ticket);
+
+        let result = self
+            .autofill_and_sign(
+                self.signer.as_ref(),
+                transaction,
+                PRESIGNED_LEDGER_WINDOW,
+                &PathSet::default(),
+            )
+            .await;
+        if result.is_err() {
+            self.sequences.release_ticket(&account, ticket);
+        }
+
+        result
+    }
+
+    /// Take an unused ticket of `account`, reading its tickets from the ledger when none
+    /// are known to be available
+    async fn take_ticket(&self, account: &str) -> Result<u32, Error> {
+        if self.sequences.available_tickets(account) == 0 {
+            let tickets = self.client_service.get_tickets(account).await?;
+            self.sequences.sync_tickets(account, &tickets);
+        }
+
+        self.sequences
+            .take_ticket(account)
+            .ok_or_else(|| {
+                Error::validation(format!(
+                    "No tickets available for {account}, create some with TicketCreate"
+                ))
+            })
+    }
+
+    /// Create `count` tickets for this account <https://xrpl.org/ticketcreate.html>
+    pub async fn create_tickets(&self, count: u32) -> Result<TransactionOutcome, Error> {
+        if count == 0 || count > MAX_TICKETS {
+            return Err(Error::validation(format!(
+                "Ticket count must be between 1 and {MAX_TICKETS}, got {count}"
+            )));
+        }
+
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+
+        info!("Creating {} tickets for {}", count, self.signer.address());
+
+        let outcome = self
+            .submit_and_wait(TicketCreateTransaction::new(account_id, count))
+            .await?;
+
+        // Pick up the new tickets on the next ticketed transaction
+        if outcome.is_success() {
+            let tickets = self.client_service.get_tickets(self.signer.address()).await?;
+            self.sequences.sync_tickets(self.signer.address(), &tickets);
+        }
+
+        Ok(outcome)
+    }
+
+    /// Tickets of this account in the validated ledger
+    pub async fn get_tickets(&self) -> Result<Vec<u32>, Error> {
+        self.client_service.get_tickets(self.signer.address()).await
+    }
+
+    /// Autofill `transaction` for its `Account` and sign it with `signer`, which may be
+    /// a regular key of that account rather than its master key. `paths` are only for
+    /// payments and are usually empty.
+    async fn prepare_transaction_with<T>(
+        &self,
+        signer: &dyn Signer,
+        mut transaction: T,
+        paths: &PathSet,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: Transaction + Clone + std::fmt::Debug,
+    {
+        let account = transaction.common().account.to_address();
+        let reserved = match transaction.common().sequence {
+            Some(_) => None,
+            None => {
+                let sequence = self.reserve_sequence(&account).await?;
+                transaction.common_mut().sequence = Some(sequence);
+                Some(sequence)
+            }
+        };
+
+        let result = self
+            .autofill_and_sign(signer, transaction, SUBMIT_LEDGER_WINDOW, paths)
+            .await;
+        if let (Err(_), Some(sequence)) = (&result, reserved) {
+            self.sequences.release(&account, sequence);
+        }
+
+        result
+    }
+
+    /// Fill in fee and `LastLedgerSequence` (`ledger_window` ledgers after the current
+    /// one), charge the fee to the fee strategy and sign `transaction` with `signer`,
+    /// appending `paths` to the signed blob
+    async fn autofill_and_sign<T>(
+        &self,
+        signer: &dyn Signer,
+        mut transaction: T,
+        ledger_window: u32,
+        paths: &PathSet,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: Transaction + Clone + std::fmt::Debug,
+    {
+        self.autofill_fee(transaction.common_mut(), ledger_window).await?;
+
+        let fee = transaction
+            .common()
+            .fee
+            .as_ref()
+            .ok_or_else(|| Error::validation("Missing fee after autofill"))?
+            .drops();
+        self.fee_strategy.charge(fee)?;
+
+        // Lets the sequence manager forget the reservation once the blob expired, should
+        // the caller never submit it
+        let common = transaction.common();
+        if let (Some(sequence), None, Some(last_ledger_sequence)) = (
+            common.sequence,
+            common.ticket_sequence,
+            common.last_ledger_sequence,
+        ) {
+            self.sequences.set_last_ledger_sequence(
+                &common.account.to_address(),
+                sequence,
+                last_ledger_sequence,
+            );
+        }
         
         info!("Transaction before signing: {:?}", transaction);
         
-        self.signer.sign_transaction(&mut transaction)?;
+        signer::sign_transaction_with_paths(signer, &mut transaction, paths).await?;
         
         info!("Transaction after signing: {:?}", transaction);
-        let tx_bytes = serialize::serialize(&transaction)
-            .map_err(|e| format!("Failed to serialize transaction: {e}"))?;
+        let mut tx_bytes = serialize::serialize(&transaction)
+            .map_err(|e| Error::validation(format!("Failed to serialize transaction: {e}")))?;
+        tx_bytes.extend(paths.encode()?);
         
         Ok(tx_bytes)
     }
 
-    /// Prepare, sign, and submit a transaction
-    async fn prepare_and_submit_transaction<T>(
+    /// Set the fee from the fee strategy and `LastLedgerSequence` to `ledger_window`
+    /// ledgers after the current one, unless they are already set
+    async fn autofill_fee(
         &self,
-        transaction: T,
-    ) -> Result<SubmitResponse, String>
+        common: &mut TransactionCommon,
+        ledger_window: u32,
+    ) -> Result<(), Error> {
+        if common.fee.is_some() && common.last_ledger_sequence.is_some() {
+            return Ok(());
+        }
+
+        let estimate = self.client_service.get_fee().await?;
+        if common.fee.is_none() {
+            let fee = self.fee_strategy.fee_for(&estimate);
+            info!("Fee of {fee} drops for {:?} policy ({estimate:?})", self.fee_strategy.policy);
+            common.fee = Some(
+                DropsAmount::from_drops(fee)
+                    .map_err(|e| Error::validation(format!("Invalid fee: {e}")))?,
+            );
+        }
+        if common.last_ledger_sequence.is_none() {
+            common.last_ledger_sequence = Some(estimate.ledger_current_index + ledger_window);
+        }
+
+        Ok(())
+    }
+
+    /// Reserve the next sequence of `account`, reading it from the ledger when the
+    /// account has nothing in flight or its local state is out of date
+    async fn reserve_sequence(&self, account: &str) -> Result<u32, Error> {
+        // Signed blobs handed to callers are never confirmed or released; drop them once
+        // they expired, as the sequence they took may have been left unused
+        if let Some(expiry) = self.sequences.earliest_expiry(account) {
+            let validated_ledger = self.validated_ledger().await?;
+            if validated_ledger > expiry {
+                self.sequences.expire(account, validated_ledger);
+            }
+        }
+
+        if self.sequences.needs_sync(account) {
+            let resp = self.client_service.get_account_info(account).await?;
+            self.sequences.sync(account, resp.account_data.sequence);
+        }
+
+        self.sequences
+            .reserve(account)
+            .ok_or_else(|| Error::validation(format!("No sequence available for {account}")))
+    }
+
+    /// Prepare and sign `transaction` with a `LastLedgerSequence`, submit it and poll until
+    /// it is validated or can no longer be, see [`Self::submit_blob_and_wait`]
+    pub async fn submit_and_wait<T>(&self, transaction: T) -> Result<TransactionOutcome, Error>
     where
         T: Transaction + Clone + std::fmt::Debug,
     {
-        let tx_blob = self.prepare_transaction(transaction.clone()).await?;
+        let tx_blob = self.prepare_transaction(transaction).await?;
+        self.submit_blob_and_wait(tx_blob).await
+    }
 
-        let req = SubmitRequest::new(hex::encode(&tx_blob));
-        let response = self
-            .client
-            .call(req)
+    /// Submit a signed blob and poll `tx` until it is in a validated ledger or the validated
+    /// ledger passes its `LastLedgerSequence`.
+    ///
+    /// Transient `ter`/`tel` results are resubmitted while waiting. A `tem` result on the
+    /// first submission means the transaction can never apply. A `tef` result, e.g.
+    /// `tefMAX_LEDGER` for a blob resubmitted after its `LastLedgerSequence`, may come from
+    /// an earlier submission that applied; if the hash is not found validated in time the
+    /// blob never applied and is reported as expired.
+    pub async fn submit_blob_and_wait(&self, tx_blob: Vec<u8>) -> Result<TransactionOutcome, Error> {
+        let fields = codec::decode_transaction(&tx_blob)?;
+        let account = fields
+            .get("Account")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| Error::validation("Transaction has no Account"))?
+            .to_string();
+        let sequence = fields
+            .get("Sequence")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0) as u32;
+        let ticket = fields
+            .get("TicketSequence")
+            .and_then(serde_json::Value::as_u64)
+            .map(|ticket| ticket as u32);
+        let last_ledger_sequence = fields
+            .get("LastLedgerSequence")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or_else(|| {
+                Error::validation(
+                    "Transaction has no LastLedgerSequence, it could be pending forever",
+                )
+            })?
+            as u32;
+
+        let outcome = self
+            .submit_and_poll(&account, &tx_blob, last_ledger_sequence)
+            .await;
+
+        let never_applied = match &outcome {
+            Ok(TransactionOutcome::Rejected { .. }) => true,
+            Ok(TransactionOutcome::Expired { .. }) => {
+                // Later transactions of the account may be waiting on this sequence
+                self.sequences.invalidate(&account);
+                true
+            }
+            // Validated, already consumed, or unknown: the next sync with the ledger
+            // settles it.
+            _ => false,
+        };
+
+        match (ticket, never_applied) {
+            (Some(ticket), true) => self.sequences.release_ticket(&account, ticket),
+            (Some(ticket), false) => self.sequences.consume_ticket(&account, ticket),
+            (None, true) => self.sequences.release(&account, sequence),
+            (None, false) => self.sequences.confirm(&account, sequence),
+        }
+
+        outcome
+    }
+
+    /// Submit `tx_blob` and poll until it is validated or the validated ledger passes
+    /// `last_ledger_sequence`. Failing calls to the server are retried until then, as the
+    /// transaction may have been submitted regardless.
+    async fn submit_and_poll(
+        &self,
+        account: &str,
+        tx_blob: &[u8],
+        last_ledger_sequence: u32,
+    ) -> Result<TransactionOutcome, Error> {
+        let hash = signer::transaction_hash(tx_blob);
+
+        // A `tef` result may come from an earlier submission of this same blob, e.g. by a
+        // resumed batch, so it only counts once the hash is not found in time
+        let mut failed_result = None;
+        let mut resubmit = true;
+        match self.send_transaction_from_bytes(tx_blob.to_vec()).await {
+            Ok(response) => {
+                info!("Submitted {}: {:?}", hash, response.engine_result);
+                self.check_sequence_result(account, response.engine_result);
+                match response.engine_result.category() {
+                    ResultCategory::Tem => {
+                        return Ok(TransactionOutcome::Rejected {
+                            hash,
+                            result: response.engine_result,
+                            message: response.engine_result_message,
+                        });
+                    }
+                    ResultCategory::Tef => {
+                        resubmit = false;
+                        failed_result = Some(response.engine_result);
+                    }
+                    _ => resubmit = is_transient(response.engine_result),
+                }
+            }
+            Err(e) => warn!("Failed to submit {hash}, resubmitting while waiting: {e}"),
+        }
+
+        let mut deadline = last_ledger_sequence;
+        loop {
+            // Read the validated ledger before looking up the transaction, so a transaction
+            // validated in between is still found.
+            let validated_ledger = match self.validated_ledger().await {
+                Ok(validated_ledger) => validated_ledger,
+                Err(e) => {
+                    warn!("Failed to get server info while waiting for {hash}: {e}");
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    continue;
+                }
+            };
+            // A blob that consumed its sequence is validated within a few ledgers, and
+            // one that did not never will be
+            if failed_result.is_some() {
+                deadline = deadline.min(validated_ledger + SUBMIT_LEDGER_WINDOW);
+            }
+
+            match self.client_service.get_validated_outcome(&hash).await {
+                Ok(Some(outcome)) => return Ok(outcome),
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Failed to look up {hash}: {e}");
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    continue;
+                }
+            }
+
+            if validated_ledger >= deadline {
+                if let Some(result) = failed_result {
+                    warn!("{hash} failed with {result:?} and was not validated, it expired");
+                }
+                // Either way the blob never applied, so a new transaction may replace it
+                return Ok(TransactionOutcome::Expired {
+                    hash,
+                    last_ledger_sequence,
+                });
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            if resubmit {
+                match self.send_transaction_from_bytes(tx_blob.to_vec()).await {
+                    Ok(response) => {
+                        info!("Resubmitted {}: {:?}", hash, response.engine_result);
+                        self.check_sequence_result(account, response.engine_result);
+                        resubmit = is_transient(response.engine_result);
+                    }
+                    Err(e) => warn!("Failed to resubmit {hash}: {e}"),
+                }
+            }
+        }
+    }
+
+    /// Resync the sequences of `account` before its next transaction when the server says
+    /// they are out of line with the ledger
+    fn check_sequence_result(&self, account: &str, result: TransactionResult) {
+        if matches!(
+            result,
+            TransactionResult::terPRE_SEQ | TransactionResult::tefPAST_SEQ
+        ) {
+            warn!("Sequences of {account} are out of date ({result:?}), resyncing");
+            self.sequences.invalidate(account);
+        }
+    }
+
+    /// Index of the latest validated ledger
+    async fn validated_ledger(&self) -> Result<u32, Error> {
+        self.client
+            .call(ServerInfoRequest::new())
             .await
-            .map_err(|e| format!("Failed to submit transaction: {e}"))?;
+            .map(|response| response.info.validated_ledger.seq)
+            .map_err(|e| Error::client("Failed to get server info", e))
+    }
 
-        Ok(response)
+    /// Create or replace the signer list of this account
+    pub async fn set_signer_list(
+        &self,
+        quorum: u32,
+        entries: &[SignerEntry],
+    ) -> Result<TransactionOutcome, Error> {
+        if entries.is_empty() || entries.len() > MAX_SIGNER_ENTRIES {
+            return Err(Error::validation(format!(
+                "A signer list needs between 1 and {MAX_SIGNER_ENTRIES} entries, got {}",
+                entries.len()
+            )));
+        }
+
+        if quorum == 0 {
+            return Err(Error::validation("Signer quorum must be greater than 0"));
+        }
+
+        let total_weight: u32 = entries.iter().map(|entry| entry.weight as u32).sum();
+        if total_weight < quorum {
+            return Err(Error::validation(format!(
+                "Signer quorum {quorum} is unreachable with a total weight of {total_weight}"
+            )));
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            if entry.weight == 0 {
+                return Err(Error::validation(format!(
+                    "Signer {} must have a weight greater than 0",
+                    entry.account
+                )));
+            }
+            if entry.account == self.signer.address() {
+                return Err(Error::validation(
+                    "An account cannot be a member of its own signer list",
+                ));
+            }
+            if entries[..i].iter().any(|other| other.account == entry.account) {
+                return Err(Error::validation(format!("Duplicate signer: {}", entry.account)));
+            }
+        }
+
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+        let tx = SignerListSetTransaction::new(account_id, quorum, entries)?;
+
+        info!(
+            "Setting signer list of {} with quorum {} and {} entries",
+            self.signer.address(),
+            quorum,
+            entries.len()
+        );
+
+        self.submit_and_wait(tx).await
+    }
+
+    /// Delete the signer list of this account
+    pub async fn delete_signer_list(&self) -> Result<TransactionOutcome, Error> {
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+
+        info!("Deleting signer list of {}", self.signer.address());
+
+        self.submit_and_wait(SignerListSetTransaction::delete(account_id))
+            .await
+    }
+
+    /// Set the regular key of this account, or remove it with `None`
+    pub async fn set_regular_key(
+        &self,
+        regular_key_address: Option<&str>,
+    ) -> Result<TransactionOutcome, Error> {
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+        let regular_key = regular_key_address
+            .map(|address| {
+                AccountId::from_address(address)
+                    .map_err(|e| Error::validation(format!("Invalid regular key address: {e}")))
+            })
+            .transpose()?;
+
+        if regular_key_address == Some(self.signer.address()) {
+            return Err(Error::validation(
+                "The regular key cannot be the master key of the account",
+            ));
+        }
+
+        info!(
+            "Setting regular key of {} to {:?}",
+            self.signer.address(),
+            regular_key_address
+        );
+
+        self.submit_and_wait(SetRegularKeyTransaction::new(account_id, regular_key))
+            .await
+    }
+
+    /// Disable the master key of this account with `asfDisableMaster`.
+    ///
+    /// Must be signed with the master key, and the account needs a regular key or a
+    /// signer list to remain usable.
+    pub async fn disable_master_key(&self) -> Result<TransactionOutcome, Error> {
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+
+        info!("Disabling master key of {}", self.signer.address());
+
+        self.submit_and_wait(AccountSetTransaction::set_flag(
+            account_id,
+            ASF_DISABLE_MASTER,
+        ))
+        .await
+    }
+
+    /// Rotate the regular key of this account to `new_key`.
+    ///
+    /// Sets the new regular key, checks the ledger reports it, proves it can sign by
+    /// submitting a no-op `AccountSet` with it, and then optionally disables the master key.
+    /// Stops at the first step that does not succeed.
+    pub async fn rotate_regular_key(
+        &self,
+        new_key: &dyn Signer,
+        disable_master: bool,
+    ) -> Result<RegularKeyRotation, Error> {
+        let regular_key = signer::derive_address(new_key.public_key());
+        let account_id = AccountId::from_address(self.signer.address())
+            .map_err(|e| Error::validation(format!("Invalid account address: {e}")))?;
+
+        let set_regular_key = self.set_regular_key(Some(&regular_key)).await?;
+        ensure_success("SetRegularKey", &set_regular_key)?;
+
+        let account_info = self.client_service.get_account_info(self.signer.address()).await?;
+        if account_info.account_data.regular_key.as_deref() != Some(regular_key.as_str()) {
+            return Err(Error::validation(format!(
+                "Regular key of {} is not {} after SetRegularKey",
+                self.signer.address(),
+                regular_key
+            )));
+        }
+
+        info!("Verifying that {} signs for {}", regular_key, self.signer.address());
+        let verification_blob = self
+            .prepare_transaction_with(
+                new_key,
+                AccountSetTransaction::new(account_id),
+                &PathSet::default(),
+            )
+            .await?;
+        let verification = self.submit_blob_and_wait(verification_blob).await?;
+        ensure_success("Regular key verification", &verification)?;
+
+        let disable_master = if disable_master {
+            let response = self.disable_master_key().await?;
+            ensure_success("Disabling the master key", &response)?;
+            Some(response)
+        } else {
+            None
+        };
+
+        Ok(RegularKeyRotation {
+            regular_key,
+            set_regular_key,
+            verification,
+            disable_master,
+        })
+    }
+
+    /// Autofill a transaction of another account (e.g. a quorum-controlled treasury)
+    /// so that `signer_count` signer list members can sign it with [`Self::sign_for`].
+    ///
+    /// Unless already set, the fee is that of the fee strategy times one plus the number of
+    /// signatures, as a multi-signed transaction costs. Signatures take a while to collect,
+    /// so `LastLedgerSequence` leaves [`PRESIGNED_LEDGER_WINDOW`] ledgers unless already set.
+    pub async fn prepare_multisigned<T>(
+        &self,
+        mut transaction: T,
+        signer_count: u32,
+    ) -> Result<T, Error>
+    where
+        T: Transaction,
+    {
+        // Reserved like in `prepare_transaction_with`, so that other transactions of the
+        // account prepared here cannot take the same sequence
+        let account = transaction.common().account.to_address();
+        let reserved = match transaction.common().sequence {
+            Some(_) => None,
+            None => {
+                let sequence = self.reserve_sequence(&account).await?;
+                transaction.common_mut().sequence = Some(sequence);
+                Some(sequence)
+            }
+        };
+
+        let result = self
+            .autofill_multisigned(transaction.common_mut(), signer_count)
+            .await;
+        match (&result, reserved) {
+            (Err(_), Some(sequence)) => self.sequences.release(&account, sequence),
+            // The signed blob is submitted elsewhere; forget the sequence once it expired
+            (Ok(last_ledger_sequence), Some(sequence)) => self.sequences.set_last_ledger_sequence(
+                &account,
+                sequence,
+                *last_ledger_sequence,
+            ),
+            _ => {}
+        }
+        result?;
+
+        Ok(transaction)
+    }
+
+    /// Fill in the `LastLedgerSequence` of a transaction with `signer_count` signers and,
+    /// unless the caller set one, the multi-signature fee; returns the former
+    async fn autofill_multisigned(
+        &self,
+        common: &mut TransactionCommon,
+        signer_count: u32,
+    ) -> Result<u32, Error> {
+        let fee_given = common.fee.is_some();
+        self.autofill_fee(common, PRESIGNED_LEDGER_WINDOW).await?;
+
+        let mut fee = common
+            .fee
+            .as_ref()
+            .ok_or_else(|| Error::validation("Missing fee after autofill"))?
+            .drops();
+        // A multi-signed transaction costs the reference fee once per signature on top
+        if !fee_given {
+            fee *= 1 + signer_count as u64;
+            common.fee = Some(
+                DropsAmount::from_drops(fee)
+                    .map_err(|e| Error::validation(format!("Invalid fee: {e}")))?,
+            );
+        }
+        self.fee_strategy.charge(fee)?;
+
+        common
+            .last_ledger_sequence
+            .ok_or_else(|| Error::validation("Missing LastLedgerSequence after autofill"))
+    }
+
+    /// Sign a prepared transaction for its `Account` as one signer list member
+    pub async fn sign_for<T>(&self, transaction: &T) -> Result<PartialSignature, Error>
+    where
+        T: Transaction + Clone,
+    {
+        multisign::sign_for(self.signer.as_ref(), transaction).await
+    }
+
+    /// Merge the partial signatures and submit through `submit_multisigned`
+    pub async fn submit_multisigned<T>(
+        &self,
+        transaction: &T,
+        signatures: &[PartialSignature],
+    ) -> Result<SubmitMultisignedResponse, Error>
+    where
+        T: Transaction + Clone,
+    {
+        let tx_blob = multisign::combine(transaction, signatures)?;
+        let tx_json = codec::decode_transaction(&tx_blob)?;
+
+        info!(
+            "Submitting multi-signed transaction with {} signatures",
+            signatures.len()
+        );
+
+        let req = SubmitMultisignedRequest::new(serde_json::Value::Object(tx_json));
+        self.client
+            .call(req)
+            .await
+            .map_err(|e| Error::client("Failed to submit multi-signed transaction", e))
     }
 
     /// Get account info using the internal client service
     pub async fn get_account_info(
         &self,
         address: Option<&str>,
-    ) -> Result<xrpl_http_client::AccountInfoResponse, String> {
+    ) -> Result<xrpl_http_client::AccountInfoResponse, Error> {
         let addr = address.unwrap_or(self.signer.address());
         self.client_service.get_account_info(addr).await
     }
@@ -255,7 +1324,7 @@ impl TransactionService {
     pub async fn get_account_currencies(
         &self,
         address: Option<&str>,
-    ) -> Result<xrpl_http_client::AccountCurrenciesResponse, String> {
+    ) -> Result<xrpl_http_client::AccountCurrenciesResponse, Error> {
         let addr = address.unwrap_or(self.signer.address());
         self.client_service.get_account_currencies(addr).await
     }
@@ -264,12 +1333,30 @@ impl TransactionService {
     pub async fn get_account_lines(
         &self,
         address: Option<&str>,
-    ) -> Result<xrpl_http_client::AccountLinesResponse, String> {
+    ) -> Result<xrpl_http_client::AccountLinesResponse, Error> {
         let addr = address.unwrap_or(self.signer.address());
         self.client_service.get_account_lines(addr).await
     }
 }
 
+/// Fail unless the transaction was validated with `tesSUCCESS`
+fn ensure_success(step: &str, outcome: &TransactionOutcome) -> Result<(), Error> {
+    match outcome.error() {
+        None => Ok(()),
+        Some(error) => {
+            warn!("{step} failed: {outcome}");
+            Err(error)
+        }
+    }
+}
+
+/// Results worth resubmitting: the transaction may still apply in a later ledger.
+/// `terQUEUED` is already held by the server.
+fn is_transient(result: TransactionResult) -> bool {
+    matches!(result.category(), ResultCategory::Ter | ResultCategory::Tel)
+        && result != TransactionResult::terQUEUED
+}
+
 impl std::fmt::Debug for TransactionService {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TransactionService")
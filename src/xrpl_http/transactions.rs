@@ -0,0 +1,286 @@
+//! Transaction types that `xrpl_types` does not model yet.
+
+use xrpl_types::{
+    AccountId, Transaction, TransactionCommon, UInt16, UInt32,
+    serialize::{Serialize, SerializeArray, Serializer},
+};
+
+use super::{error::Error, memo::Memo, types::SignerEntry};
+
+/// Transaction type codes <https://xrpl.org/transaction-types.html>
+const ACCOUNT_SET: UInt16 = 3;
+const SET_REGULAR_KEY: UInt16 = 5;
+const TICKET_CREATE: UInt16 = 10;
+const SIGNER_LIST_SET: UInt16 = 12;
+
+/// `asfDisableMaster`: disallow signing with the master key <https://xrpl.org/accountset.html#accountset-flags>
+pub const ASF_DISABLE_MASTER: UInt32 = 4;
+
+/// Maximum number of tickets an account can hold <https://xrpl.org/tickets.html#limitations>
+pub const MAX_TICKETS: u32 = 250;
+
+/// Maximum number of entries in a signer list <https://xrpl.org/signerlist.html>
+pub const MAX_SIGNER_ENTRIES: usize = 32;
+
+/// An entry of `SignerEntries`, with the account already decoded
+#[derive(Debug, Clone)]
+struct SignerEntryFields {
+    account: AccountId,
+    signer_weight: UInt16,
+}
+
+impl Serialize for SignerEntryFields {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_account_id("Account", AccountId(self.account.0))?;
+        serializer.serialize_uint16("SignerWeight", self.signer_weight)
+    }
+}
+
+/// A `SignerListSet` transaction <https://xrpl.org/signerlistset.html>
+#[derive(Debug, Clone)]
+pub struct SignerListSetTransaction {
+    pub common: TransactionCommon,
+    pub signer_quorum: UInt32,
+    signer_entries: Vec<SignerEntryFields>,
+}
+
+impl SignerListSetTransaction {
+    /// Create or replace the signer list of `account`
+    pub fn new(account: AccountId, signer_quorum: UInt32, entries: &[SignerEntry]) -> Result<Self, Error> {
+        let signer_entries = entries
+            .iter()
+            .map(|entry| {
+                Ok(SignerEntryFields {
+                    account: AccountId::from_address(&entry.account)
+                        .map_err(|e| {
+                            Error::validation(format!(
+                                "Invalid signer address {}: {e}",
+                                entry.account
+                            ))
+                        })?,
+                    signer_weight: entry.weight,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            common: TransactionCommon::new(account),
+            signer_quorum,
+            signer_entries,
+        })
+    }
+
+    /// Delete the signer list of `account`: a quorum of 0 and no entries
+    pub fn delete(account: AccountId) -> Self {
+        Self {
+            common: TransactionCommon::new(account),
+            signer_quorum: 0,
+            signer_entries: Vec::new(),
+        }
+    }
+}
+
+impl Serialize for SignerListSetTransaction {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_uint16("TransactionType", SIGNER_LIST_SET)?;
+        self.common.serialize(serializer)?;
+        serializer.serialize_uint32("Flags", 0)?;
+        serializer.serialize_uint32("SignerQuorum", self.signer_quorum)?;
+
+        if !self.signer_entries.is_empty() {
+            let mut array = serializer.serialize_array("SignerEntries")?;
+            for entry in &self.signer_entries {
+                array.serialize_object("SignerEntry", entry)?;
+            }
+            array.end()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Transaction for SignerListSetTransaction {
+    fn common(&self) -> &TransactionCommon {
+        &self.common
+    }
+
+    fn common_mut(&mut self) -> &mut TransactionCommon {
+        &mut self.common
+    }
+}
+
+/// A `SetRegularKey` transaction <https://xrpl.org/setregularkey.html>
+#[derive(Debug, Clone)]
+pub struct SetRegularKeyTransaction {
+    pub common: TransactionCommon,
+    /// The new regular key, or `None` to remove the current one
+    pub regular_key: Option<AccountId>,
+}
+
+impl SetRegularKeyTransaction {
+    pub fn new(account: AccountId, regular_key: Option<AccountId>) -> Self {
+        Self {
+            common: TransactionCommon::new(account),
+            regular_key,
+        }
+    }
+}
+
+impl Serialize for SetRegularKeyTransaction {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_uint16("TransactionType", SET_REGULAR_KEY)?;
+        self.common.serialize(serializer)?;
+        serializer.serialize_uint32("Flags", 0)?;
+
+        if let Some(regular_key) = &self.regular_key {
+            serializer.serialize_account_id("RegularKey", AccountId(regular_key.0))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Transaction for SetRegularKeyTransaction {
+    fn common(&self) -> &TransactionCommon {
+        &self.common
+    }
+
+    fn common_mut(&mut self) -> &mut TransactionCommon {
+        &mut self.common
+    }
+}
+
+/// An `AccountSet` transaction limited to setting and clearing account flags
+/// <https://xrpl.org/accountset.html>
+#[derive(Debug, Clone)]
+pub struct AccountSetTransaction {
+    pub common: TransactionCommon,
+    pub set_flag: Option<UInt32>,
+    pub clear_flag: Option<UInt32>,
+}
+
+impl AccountSetTransaction {
+    /// An `AccountSet` that changes nothing, useful to prove a key can sign for `account`
+    pub fn new(account: AccountId) -> Self {
+        Self {
+            common: TransactionCommon::new(account),
+            set_flag: None,
+            clear_flag: None,
+        }
+    }
+
+    pub fn set_flag(account: AccountId, flag: UInt32) -> Self {
+        Self {
+            set_flag: Some(flag),
+            ..Self::new(account)
+        }
+    }
+
+    pub fn clear_flag(account: AccountId, flag: UInt32) -> Self {
+        Self {
+            clear_flag: Some(flag),
+            ..Self::new(account)
+        }
+    }
+}
+
+impl Serialize for AccountSetTransaction {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_uint16("TransactionType", ACCOUNT_SET)?;
+        self.common.serialize(serializer)?;
+        serializer.serialize_uint32("Flags", 0)?;
+
+        if let Some(set_flag) = self.set_flag {
+            serializer.serialize_uint32("SetFlag", set_flag)?;
+        }
+        if let Some(clear_flag) = self.clear_flag {
+            serializer.serialize_uint32("ClearFlag", clear_flag)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Transaction for AccountSetTransaction {
+    fn common(&self) -> &TransactionCommon {
+        &self.common
+    }
+
+    fn common_mut(&mut self) -> &mut TransactionCommon {
+        &mut self.common
+    }
+}
+
+/// A `TicketCreate` transaction <https://xrpl.org/ticketcreate.html>
+#[derive(Debug, Clone)]
+pub struct TicketCreateTransaction {
+    pub common: TransactionCommon,
+    pub ticket_count: UInt32,
+}
+
+impl TicketCreateTransaction {
+    pub fn new(account: AccountId, ticket_count: UInt32) -> Self {
+        Self {
+            common: TransactionCommon::new(account),
+            ticket_count,
+        }
+    }
+}
+
+impl Serialize for TicketCreateTransaction {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        serializer.serialize_uint16("TransactionType", TICKET_CREATE)?;
+        self.common.serialize(serializer)?;
+        serializer.serialize_uint32("Flags", 0)?;
+        serializer.serialize_uint32("TicketCount", self.ticket_count)
+    }
+}
+
+impl Transaction for TicketCreateTransaction {
+    fn common(&self) -> &TransactionCommon {
+        &self.common
+    }
+
+    fn common_mut(&mut self) -> &mut TransactionCommon {
+        &mut self.common
+    }
+}
+
+/// Any transaction with a `Memos` array, which `TransactionCommon` does not carry
+#[derive(Debug, Clone)]
+pub struct WithMemos<T> {
+    pub transaction: T,
+    pub memos: Vec<Memo>,
+}
+
+impl<T> WithMemos<T> {
+    pub fn new(transaction: T, memos: Vec<Memo>) -> Self {
+        Self { transaction, memos }
+    }
+}
+
+impl<T: Transaction> Serialize for WithMemos<T> {
+    fn serialize<S: Serializer>(&self, serializer: &mut S) -> Result<(), S::Error> {
+        self.transaction.serialize(serializer)?;
+
+        if !self.memos.is_empty() {
+            let mut array = serializer.serialize_array("Memos")?;
+            for memo in &self.memos {
+                array.serialize_object("Memo", memo)?;
+            }
+            array.end()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Transaction> Transaction for WithMemos<T> {
+    fn common(&self) -> &TransactionCommon {
+        self.transaction.common()
+    }
+
+    fn common_mut(&mut self) -> &mut TransactionCommon {
+        self.transaction.common_mut()
+    }
+}
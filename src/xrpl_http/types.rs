@@ -1,18 +1,23 @@
-use bigdecimal::{BigDecimal, ToPrimitive};
 use serde::{Deserialize, Serialize};
-use std::{fmt, str::FromStr};
-use tracing::info;
-use xrpl_types::{AccountId, Amount, CurrencyCode, DropsAmount, IssuedAmount, IssuedValue};
+use std::fmt;
+use xrpl_http_client::{Meta, ResultCategory, TransactionResult};
+use xrpl_types::{Amount, PaymentTransaction, Transaction};
 
-use crate::xrpl_http::ClientService;
+use crate::xrpl_http::{
+    amount::Decimal, error::Error, memo::{self, Memo}, token::Token, transactions::WithMemos,
+};
+
+/// Amount out of a swap payment; with partial payments the ledger delivers whatever the
+/// offers give above `amount_out_min`
+const MAX_AMOUNT_OUT: &str = "1000000000";
 
 /// Request structure for token swaps on XRPL
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwapRequest {
-    /// Token to send (use "XRP" for native XRP)
-    pub token_in: String,
-    /// Token to receive (use "XRP" for native XRP)
-    pub token_out: String,
+    /// Token to send
+    pub token_in: Token,
+    /// Token to receive
+    pub token_out: Token,
     /// Amount to send (as string to preserve precision)
     pub amount_in: String,
     /// Minimum amount to receive (as string to preserve precision)
@@ -22,8 +27,8 @@ pub struct SwapRequest {
 impl SwapRequest {
     /// Create a new swap request
     pub fn new(
-        token_in: String,
-        token_out: String,
+        token_in: Token,
+        token_out: Token,
         amount_in: String,
         amount_out_min: String,
     ) -> Self {
@@ -37,174 +42,34 @@ impl SwapRequest {
 
     /// Check if this is an XRP-to-token swap
     pub fn is_xrp_to_token(&self) -> bool {
-        self.token_in == "XRP" && self.token_out != "XRP"
+        self.token_in.is_xrp() && !self.token_out.is_xrp()
     }
 
     /// Check if this is a token-to-XRP swap
     pub fn is_token_to_xrp(&self) -> bool {
-        self.token_in != "XRP" && self.token_out == "XRP"
+        !self.token_in.is_xrp() && self.token_out.is_xrp()
     }
 
     /// Check if this is a token-to-token swap
     pub fn is_token_to_token(&self) -> bool {
-        self.token_in != "XRP" && self.token_out != "XRP"
+        !self.token_in.is_xrp() && !self.token_out.is_xrp()
     }
 
     /// Check if this is an XRP-to-XRP swap (which doesn't make sense)
     pub fn is_xrp_to_xrp(&self) -> bool {
-        self.token_in == "XRP" && self.token_out == "XRP"
-    }
-
-    fn parse_issued_value(&self, amount_str: &str) -> Result<IssuedValue, String> {
-        let value = BigDecimal::from_str(amount_str)
-            .map_err(|e| format!("Invalid amount format: {e}"))?;
-
-        let (value_big_int, scale) = value.into_bigint_and_scale();
-
-        let mantissa = value_big_int
-            .to_i64()
-            .ok_or("Amount too large for mantissa conversion")?;
-
-        let exponent = -(scale as i8);
-
-        info!(
-            "Parsed amount - mantissa: {}, exponent: {}",
-            mantissa, exponent
-        );
-
-        IssuedValue::from_mantissa_exponent(mantissa, exponent)
-            .map_err(|e| format!("Failed to create issued value: {e}"))
+        self.token_in.is_xrp() && self.token_out.is_xrp()
     }
 
-    pub async fn get_max_amount_out(&self) -> Result<Amount, String> {
-        let client_service = ClientService::new();
-        let base_amount_out = "1000000000";
-        if self.token_out == "XRP" {
-            let xrp_amount = BigDecimal::from_str(base_amount_out)
-                .map_err(|e| format!("Invalid XRP amount: {e}"))?;
-
-            let drops = xrp_amount
-                * BigDecimal::from_str("1000000")
-                    .map_err(|e| format!("Failed to calculate drops: {e}"))?;
-
-            let drops_u64 = drops
-                .to_u64()
-                .ok_or("Amount too large for drops conversion")?;
-
-            let drops_amount = DropsAmount::from_drops(drops_u64)
-                .map_err(|e| format!("Invalid drops amount: {e}"))?;
-
-            Ok(Amount::Drops(drops_amount))
-        } else {
-            let currencies = client_service
-                .get_account_currencies(&self.token_out)
-                .await?;
-
-            if currencies.receive_currencies.is_empty() {
-                return Err(format!("No currencies found for token: {}", self.token_out));
-            }
-
-            let currency_code = &currencies.receive_currencies[0];
-            let currency = CurrencyCode::from_str(currency_code)
-                .map_err(|e| format!("Invalid currency code: {e}"))?;
-
-            let token = &self.token_out.clone();
-            let issued_value = self.parse_issued_value(base_amount_out)?;
-
-            let token_id = AccountId::from_address(token)
-                .map_err(|e| format!("Invalid token address: {e}"))?;
-
-            let issued_amount = IssuedAmount::from_issued_value(issued_value, currency, token_id)
-                .map_err(|e| format!("Failed to create issued amount: {e}"))?;
-
-            Ok(Amount::Issued(issued_amount))
-        }
+    pub fn get_max_amount_out(&self) -> Result<Amount, Error> {
+        self.token_out.amount(&MAX_AMOUNT_OUT.parse()?)
     }
 
-    pub async fn get_send_max(&self) -> Result<Amount, String> {
-        let client_service = ClientService::new();
-        if self.token_in == "XRP" {
-            let xrp_amount = BigDecimal::from_str(&self.amount_in)
-                .map_err(|e| format!("Invalid XRP amount: {e}"))?;
-
-            let drops = xrp_amount
-                * BigDecimal::from_str("1000000")
-                    .map_err(|e| format!("Failed to calculate drops: {e}"))?;
-
-            let drops_u64 = drops
-                .to_u64()
-                .ok_or("Amount too large for drops conversion")?;
-
-            let drops_amount = DropsAmount::from_drops(drops_u64)
-                .map_err(|e| format!("Invalid drops amount: {e}"))?;
-
-            Ok(Amount::Drops(drops_amount))
-        } else {
-            let currencies = client_service
-                .get_account_currencies(&self.token_in)
-                .await?;
-
-            if currencies.receive_currencies.is_empty() {
-                return Err(format!("No currencies found for token: {}", self.token_in));
-            }
-
-            let currency_code = &currencies.receive_currencies[0];
-            let currency = CurrencyCode::from_str(currency_code)
-                .map_err(|e| format!("Invalid currency code: {e}"))?;
-
-            let issued_value = self.parse_issued_value(&self.amount_in)?;
-
-            let token_id = AccountId::from_address(&self.token_in.to_string())
-                .map_err(|e| format!("Invalid token address: {e}"))?;
-
-            let issued_amount = IssuedAmount::from_issued_value(issued_value, currency, token_id)
-                .map_err(|e| format!("Failed to create issued amount: {e}"))?;
-
-            Ok(Amount::Issued(issued_amount))
-        }
+    pub fn get_send_max(&self) -> Result<Amount, Error> {
+        self.token_in.amount(&self.amount_in.parse()?)
     }
 
-    pub async fn get_receive_min(&self) -> Result<Amount, String> {
-        let client_service = ClientService::new();
-        if self.token_out == "XRP" {
-            let xrp_amount = BigDecimal::from_str(&self.amount_out_min)
-                .map_err(|e| format!("Invalid XRP amount: {e}"))?;
-
-            let drops = xrp_amount
-                * BigDecimal::from_str("1000000")
-                    .map_err(|e| format!("Failed to calculate drops: {e}"))?;
-
-            let drops_u64 = drops
-                .to_u64()
-                .ok_or("Amount too large for drops conversion")?;
-
-            let drops_amount = DropsAmount::from_drops(drops_u64)
-                .map_err(|e| format!("Invalid drops amount: {e}"))?;
-
-            Ok(Amount::Drops(drops_amount))
-        } else {
-            let currencies = client_service
-                .get_account_currencies(&self.token_out)
-                .await?;
-
-            if currencies.receive_currencies.is_empty() {
-                return Err(format!("No currencies found for token: {}", self.token_out));
-            }
-
-            let currency_code = &currencies.receive_currencies[0];
-            let currency = CurrencyCode::from_str(currency_code)
-                .map_err(|e| format!("Invalid currency code: {e}"))?;
-
-            let issued_value = self.parse_issued_value(&self.amount_out_min)?;
-
-            let token_id = AccountId::from_address(&self.token_out.to_string())
-                .map_err(|e| format!("Invalid token address: {e}"))?;
-
-            let issued_amount = IssuedAmount::from_issued_value(issued_value, currency, token_id)
-                .map_err(|e| format!("Failed to create issued amount: {e}"))?;
-
-            Ok(Amount::Issued(issued_amount))
-        }
+    pub fn get_receive_min(&self) -> Result<Amount, Error> {
+        self.token_out.amount(&self.amount_out_min.parse()?)
     }
 
     /// Validate the swap request
@@ -213,16 +78,11 @@ impl SwapRequest {
             return Err(SwapError::InvalidSwap("Cannot swap XRP to XRP".to_string()));
         }
 
-        if self.token_in.is_empty() {
-            return Err(SwapError::InvalidToken(
-                "token_in cannot be empty".to_string(),
-            ));
-        }
-
-        if self.token_out.is_empty() {
-            return Err(SwapError::InvalidToken(
-                "token_out cannot be empty".to_string(),
-            ));
+        if self.token_in == self.token_out {
+            return Err(SwapError::InvalidSwap(format!(
+                "Cannot swap {} to itself",
+                self.token_in
+            )));
         }
 
         if self.amount_in.is_empty() {
@@ -237,14 +97,21 @@ impl SwapRequest {
             ));
         }
 
-        // Try to parse amounts to validate they're numeric
-        self.amount_in.parse::<f64>().map_err(|_| {
-            SwapError::InvalidAmount("amount_in must be a valid number".to_string())
-        })?;
-
-        self.amount_out_min.parse::<f64>().map_err(|_| {
-            SwapError::InvalidAmount("amount_out_min must be a valid number".to_string())
-        })?;
+        // Amounts must be exact values the ledger can hold in their token
+        for (name, token, amount) in [
+            ("amount_in", &self.token_in, &self.amount_in),
+            ("amount_out_min", &self.token_out, &self.amount_out_min),
+        ] {
+            let value = amount
+                .parse::<Decimal>()
+                .map_err(|_| SwapError::InvalidAmount(format!("{name} must be a valid number")))?;
+            if !value.is_positive() {
+                return Err(SwapError::InvalidAmount(format!("{name} must be positive")));
+            }
+            token
+                .amount(&value)
+                .map_err(|e| SwapError::InvalidAmount(format!("{name}: {e}")))?;
+        }
 
         Ok(())
     }
@@ -253,24 +120,70 @@ impl SwapRequest {
 /// Request structure for creating trust lines
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrustLineRequest {
-    /// Token address to create trust line for
-    pub token_address: String,
+    /// Token to create the trust line for
+    pub token: Token,
     /// Optional limit for the trust line (defaults to a reasonable amount)
     pub limit: Option<String>,
 }
 
 impl TrustLineRequest {
     /// Create a new trust line request
-    pub fn new(token_address: String, limit: Option<String>) -> Self {
-        Self {
-            token_address,
-            limit,
-        }
+    pub fn new(token: Token, limit: Option<String>) -> Self {
+        Self { token, limit }
     }
 
     /// Create a new trust line request with default limit
-    pub fn with_default_limit(token_address: String) -> Self {
-        Self::new(token_address, None)
+    pub fn with_default_limit(token: Token) -> Self {
+        Self::new(token, None)
+    }
+}
+
+/// Optional fields of a payment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaymentOptions {
+    /// Identifies the recipient at the destination, e.g. the customer of an exchange. Must
+    /// agree with the tag of an X-address destination.
+    pub destination_tag: Option<u32>,
+    /// Identifies the sender or the reason of the payment at the source
+    pub source_tag: Option<u32>,
+    pub memos: Vec<Memo>,
+}
+
+impl PaymentOptions {
+    pub fn with_destination_tag(mut self, destination_tag: u32) -> Self {
+        self.destination_tag = Some(destination_tag);
+        self
+    }
+
+    pub fn with_source_tag(mut self, source_tag: u32) -> Self {
+        self.source_tag = Some(source_tag);
+        self
+    }
+
+    pub fn with_memo(mut self, memo: Memo) -> Self {
+        self.memos.push(memo);
+        self
+    }
+
+    /// `payment` with these options. A destination tag already set on it, e.g. from an
+    /// X-address, must agree with the one of the options, and the memos must fit in
+    /// [`memo::MAX_MEMOS_SIZE`].
+    pub fn apply(
+        &self,
+        mut payment: PaymentTransaction,
+    ) -> Result<WithMemos<PaymentTransaction>, Error> {
+        payment.destination_tag = match (payment.destination_tag, self.destination_tag) {
+            (Some(tag), Some(option_tag)) if tag != option_tag => {
+                return Err(Error::validation(format!(
+                    "Destination tag {option_tag} conflicts with destination tag {tag}"
+                )));
+            }
+            (tag, option_tag) => tag.or(option_tag),
+        };
+        payment.common_mut().source_tag = self.source_tag;
+        memo::check_size(&self.memos)?;
+
+        Ok(WithMemos::new(payment, self.memos.clone()))
     }
 }
 
@@ -280,8 +193,71 @@ pub enum SwapError {
     InvalidSwap(String),
     InvalidToken(String),
     InvalidAmount(String),
+    /// The server refused the request, e.g. while busy; nothing was submitted
     NetworkError(String),
     TransactionError(String),
+    /// No offers or paths could deliver the minimum amount out (`tecPATH_DRY`, `tecPATH_PARTIAL`)
+    NoLiquidity(String),
+    /// The account cannot fund the amount in and the fee
+    InsufficientFunds(String),
+    /// The account has no usable trust line for the token it receives
+    NoTrustLine(String),
+    /// The swap may have been submitted and can still be validated, e.g. after a lost
+    /// connection or a `tel`/`ter` result. Look up its hash before trying again.
+    Unknown(String),
+    /// The swap was not validated before its `LastLedgerSequence`; it never took effect
+    Expired(String),
+    SigningError(String),
+}
+
+impl SwapError {
+    /// Whether submitting the same swap again is safe and may succeed, as the failed one
+    /// can no longer apply
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, SwapError::NetworkError(_) | SwapError::Expired(_))
+    }
+}
+
+impl From<Error> for SwapError {
+    fn from(error: Error) -> Self {
+        let message = error.to_string();
+        match error.engine_result() {
+            Some(
+                TransactionResult::tecPATH_DRY
+                | TransactionResult::tecPATH_PARTIAL
+                | TransactionResult::tecKILLED,
+            ) => return SwapError::NoLiquidity(message),
+            Some(
+                TransactionResult::tecUNFUNDED
+                | TransactionResult::tecUNFUNDED_PAYMENT
+                | TransactionResult::tecINSUFFICIENT_FUNDS
+                | TransactionResult::tecINSUFF_FEE
+                | TransactionResult::terINSUF_FEE_B,
+            ) => return SwapError::InsufficientFunds(message),
+            Some(
+                TransactionResult::tecNO_LINE
+                | TransactionResult::tecNO_LINE_INSUF_RESERVE
+                | TransactionResult::tecNO_AUTH
+                | TransactionResult::terNO_LINE
+                | TransactionResult::terNO_AUTH,
+            ) => return SwapError::NoTrustLine(message),
+            _ => {}
+        }
+
+        match error {
+            Error::Transport(_) | Error::Local { .. } | Error::Retry { .. } => {
+                SwapError::Unknown(message)
+            }
+            Error::Rpc { .. } if error.is_retryable() => SwapError::NetworkError(message),
+            Error::Expired { .. } => SwapError::Expired(message),
+            Error::Validation(_) => SwapError::InvalidSwap(message),
+            Error::Signing(_) | Error::Keystore(_) => SwapError::SigningError(message),
+            Error::Rpc { .. }
+            | Error::Claimed { .. }
+            | Error::Failed { .. }
+            | Error::Malformed { .. } => SwapError::TransactionError(message),
+        }
+    }
 }
 
 impl fmt::Display for SwapError {
@@ -292,10 +268,18 @@ impl fmt::Display for SwapError {
             SwapError::InvalidAmount(msg) => write!(f, "Invalid amount: {msg}"),
             SwapError::NetworkError(msg) => write!(f, "Network error: {msg}"),
             SwapError::TransactionError(msg) => write!(f, "Transaction error: {msg}"),
+            SwapError::NoLiquidity(msg) => write!(f, "No liquidity: {msg}"),
+            SwapError::InsufficientFunds(msg) => write!(f, "Insufficient funds: {msg}"),
+            SwapError::NoTrustLine(msg) => write!(f, "No trust line: {msg}"),
+            SwapError::Unknown(msg) => write!(f, "Unknown outcome, check the hash: {msg}"),
+            SwapError::Expired(msg) => write!(f, "Expired: {msg}"),
+            SwapError::SigningError(msg) => write!(f, "Signing error: {msg}"),
         }
     }
 }
 
+impl std::error::Error for SwapError {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FulfillmentDetails {
     pub amount_out: String,
@@ -306,3 +290,261 @@ pub struct FulfillmentDetails {
     pub tx_signer: String,
     pub tx_timestamp: u64,
 }
+
+/// A member of an account's signer list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerEntry {
+    /// Address of the member
+    pub account: String,
+    /// Weight of the member's signature towards the quorum
+    pub weight: u16,
+}
+
+impl SignerEntry {
+    pub fn new(account: String, weight: u16) -> Self {
+        Self { account, weight }
+    }
+}
+
+/// The signer list of an account <https://xrpl.org/signerlist.html>
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignerList {
+    pub quorum: u32,
+    pub entries: Vec<SignerEntry>,
+}
+
+impl<'de> Deserialize<'de> for SignerList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct SignerEntryRaw {
+            account: String,
+            signer_weight: u16,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct SignerEntryElmRaw {
+            signer_entry: SignerEntryRaw,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct SignerListRaw {
+            signer_quorum: u32,
+            signer_entries: Vec<SignerEntryElmRaw>,
+        }
+
+        let raw = SignerListRaw::deserialize(deserializer)?;
+
+        Ok(SignerList {
+            quorum: raw.signer_quorum,
+            entries: raw
+                .signer_entries
+                .into_iter()
+                .map(|elm| SignerEntry::new(elm.signer_entry.account, elm.signer_entry.signer_weight))
+                .collect(),
+        })
+    }
+}
+
+/// Outcome of [`TransactionService::rotate_regular_key`](super::TransactionService::rotate_regular_key)
+#[derive(Debug)]
+pub struct RegularKeyRotation {
+    /// Address derived from the new regular key
+    pub regular_key: String,
+    pub set_regular_key: TransactionOutcome,
+    /// No-op `AccountSet` signed with the new regular key
+    pub verification: TransactionOutcome,
+    /// `AccountSet` with `asfDisableMaster`, when requested
+    pub disable_master: Option<TransactionOutcome>,
+}
+
+/// Where the sequence of a transaction comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceSource {
+    /// The next account sequence number
+    Sequence(u32),
+    /// A ticket created earlier with `TicketCreate`; `Sequence` is then 0
+    Ticket(u32),
+}
+
+/// Everything `prepare_transaction` would otherwise fetch from the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineSigningParams {
+    pub sequence: SequenceSource,
+    /// Fee in drops
+    pub fee: u64,
+    pub last_ledger_sequence: u32,
+}
+
+/// A signed transaction ready to submit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    pub tx_blob: Vec<u8>,
+    /// Hex encoded transaction hash, known before submission
+    pub hash: String,
+}
+
+/// Final outcome of [`TransactionService::submit_and_wait`](super::TransactionService::submit_and_wait)
+#[derive(Debug, Clone)]
+pub enum TransactionOutcome {
+    /// Included in a validated ledger. The result can still be a `tec` failure,
+    /// which only claimed the fee.
+    Validated {
+        hash: String,
+        result: TransactionResult,
+        ledger_index: u32,
+        meta: Meta,
+    },
+    /// Refused by the server with a `tem` result; it was never applied
+    Rejected {
+        hash: String,
+        result: TransactionResult,
+        message: String,
+    },
+    /// Not validated before the ledger passed `LastLedgerSequence`, or refused with a `tef`
+    /// result and not validated in time; it can no longer apply
+    Expired {
+        hash: String,
+        last_ledger_sequence: u32,
+    },
+}
+
+impl TransactionOutcome {
+    pub fn hash(&self) -> &str {
+        match self {
+            TransactionOutcome::Validated { hash, .. }
+            | TransactionOutcome::Rejected { hash, .. }
+            | TransactionOutcome::Expired { hash, .. } => hash,
+        }
+    }
+
+    /// The engine result, unless the transaction expired
+    pub fn result(&self) -> Option<TransactionResult> {
+        match self {
+            TransactionOutcome::Validated { result, .. }
+            | TransactionOutcome::Rejected { result, .. } => Some(*result),
+            TransactionOutcome::Expired { .. } => None,
+        }
+    }
+
+    /// Whether the transaction was validated with `tesSUCCESS`
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self,
+            TransactionOutcome::Validated { result, .. } if result.category() == ResultCategory::Tes
+        )
+    }
+
+    /// The failure as an [`Error`], or `None` if the transaction succeeded
+    pub fn error(&self) -> Option<Error> {
+        match self {
+            _ if self.is_success() => None,
+            TransactionOutcome::Validated {
+                hash,
+                result,
+                ledger_index,
+                ..
+            } => Some(Error::engine(
+                *result,
+                format!("{hash} validated in ledger {ledger_index}"),
+            )),
+            TransactionOutcome::Rejected {
+                result, message, ..
+            } => Some(Error::engine(*result, message.clone())),
+            TransactionOutcome::Expired {
+                hash,
+                last_ledger_sequence,
+            } => Some(Error::Expired {
+                hash: hash.clone(),
+                last_ledger_sequence: *last_ledger_sequence,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for TransactionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionOutcome::Validated {
+                hash,
+                result,
+                ledger_index,
+                ..
+            } => write!(f, "{hash} validated in ledger {ledger_index} with {result:?}"),
+            TransactionOutcome::Rejected {
+                hash,
+                result,
+                message,
+            } => write!(f, "{hash} rejected with {result:?}: {message}"),
+            TransactionOutcome::Expired {
+                hash,
+                last_ledger_sequence,
+            } => write!(f, "{hash} expired after ledger {last_ledger_sequence}"),
+        }
+    }
+}
+
+/// Account reserves of the validated ledger, in drops <https://xrpl.org/reserves.html>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountReserves {
+    /// Reserve of every account
+    pub base: u64,
+    /// Additional reserve per object the account owns
+    pub owner: u64,
+}
+
+impl AccountReserves {
+    /// Reserve of an account owning `owner_count` objects
+    pub fn for_owner_count(&self, owner_count: u32) -> u64 {
+        self.base + self.owner * owner_count as u64
+    }
+}
+
+/// Change of one balance of one account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub account: String,
+    /// "XRP" or a currency code
+    pub currency: String,
+    /// Issuer of the currency, `None` for XRP
+    pub issuer: Option<String>,
+    /// Signed change, in XRP rather than drops for XRP
+    pub value: Decimal,
+}
+
+/// Where a [`Simulation`] comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SimulationSource {
+    /// The server applied the transaction to a copy of its open ledger
+    Server,
+    /// The server cannot simulate, so only the account, sequence, fee and funding were
+    /// checked locally; paths and offers were not
+    Preflight,
+}
+
+/// Expected outcome of a transaction that was not submitted
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub result: TransactionResult,
+    pub message: String,
+    pub source: SimulationSource,
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+impl Simulation {
+    /// Whether the transaction is expected to succeed with `tesSUCCESS`
+    pub fn is_success(&self) -> bool {
+        self.result.category() == ResultCategory::Tes
+    }
+
+    /// The expected failure as an [`Error`], or `None` if it is expected to succeed
+    pub fn error(&self) -> Option<Error> {
+        (!self.is_success()).then(|| Error::engine(self.result, self.message.clone()))
+    }
+}
@@ -0,0 +1,162 @@
+//! Verification of signed transaction blobs received from another party before submitting them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use xrpl_binary_codec::hash;
+
+use super::{
+    address,
+    amount::Decimal,
+    codec::{self, TF_PARTIAL_PAYMENT, string_field},
+    error::Error,
+    signer,
+    token::Token,
+};
+
+/// A single-signed transaction whose signature and signing key have been checked
+#[derive(Debug, Clone)]
+pub struct VerifiedTransaction {
+    /// Hex encoded transaction hash
+    pub hash: String,
+    pub account: String,
+    pub transaction_type: String,
+    /// All fields of the transaction in their JSON representation
+    pub fields: Map<String, Value>,
+}
+
+/// The payment a counterparty agreed to send
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedPayment {
+    /// Classic address, or X-address when the payment must carry its destination tag
+    pub destination: String,
+    /// Currency code, or "XRP"
+    pub currency: String,
+    /// Issuer of the currency, `None` for XRP
+    pub issuer: Option<String>,
+    /// Amount in XRP rather than drops for XRP, like [`Token::amount`]
+    pub value: Decimal,
+}
+
+impl ExpectedPayment {
+    pub fn new(destination: String, currency: String, issuer: Option<String>, value: Decimal) -> Self {
+        Self {
+            destination,
+            currency,
+            issuer,
+            value,
+        }
+    }
+
+    /// A payment of `value` of `token`, in XRP rather than drops for XRP
+    pub fn for_token(destination: String, token: &Token, value: Decimal) -> Self {
+        Self::new(
+            destination,
+            token.currency().to_string(),
+            token.issuer().map(str::to_string),
+            value,
+        )
+    }
+}
+
+/// Decode `tx_blob`, check its `TxnSignature` against `SigningPubKey` and that the key
+/// is the master key of the claimed `Account`
+pub fn verify_transaction(tx_blob: &[u8]) -> Result<VerifiedTransaction, Error> {
+    let fields = codec::decode_transaction(tx_blob)?;
+
+    let account = string_field(&fields, "Account")?.to_string();
+    let transaction_type = string_field(&fields, "TransactionType")?.to_string();
+    let public_key = hex::decode(string_field(&fields, "SigningPubKey")?)
+        .map_err(|e| Error::validation(format!("Invalid SigningPubKey: {e}")))?;
+    let signature = hex::decode(string_field(&fields, "TxnSignature")?)
+        .map_err(|e| Error::validation(format!("Invalid TxnSignature: {e}")))?;
+
+    if public_key.is_empty() {
+        return Err(Error::validation("Multi-signed transactions are not supported"));
+    }
+
+    let signing_data = codec::signing_data(tx_blob)?;
+    let payload = [&hash::HASH_PREFIX_UNSIGNED_TRANSACTION_SINGLE[..], &signing_data].concat();
+    if !signer::verify_payload(&public_key, &payload, &signature)? {
+        return Err(Error::signing("Transaction signature does not match SigningPubKey"));
+    }
+
+    let signing_address = signer::derive_address(&public_key);
+    if signing_address != account {
+        return Err(Error::signing(format!(
+            "SigningPubKey belongs to {signing_address}, not to the transaction account {account}"
+        )));
+    }
+
+    Ok(VerifiedTransaction {
+        hash: signer::transaction_hash(tx_blob),
+        account,
+        transaction_type,
+        fields,
+    })
+}
+
+impl VerifiedTransaction {
+    /// Check that this is a payment delivering exactly `expected`: the destination and
+    /// amount match and partial payments, which may deliver less, are refused
+    pub fn check_payment(&self, expected: &ExpectedPayment) -> Result<(), Error> {
+        if self.transaction_type != "Payment" {
+            return Err(Error::validation(format!(
+                "Expected a Payment, got {}",
+                self.transaction_type
+            )));
+        }
+
+        let expected_destination = address::decode_address(&expected.destination)?;
+        let destination = string_field(&self.fields, "Destination")?;
+        if destination != expected_destination.classic_address {
+            return Err(Error::validation(format!(
+                "Payment goes to {destination}, expected {}",
+                expected.destination
+            )));
+        }
+
+        let destination_tag = self
+            .fields
+            .get("DestinationTag")
+            .and_then(Value::as_u64)
+            .map(|tag| tag as u32);
+        if expected_destination.tag.is_some() && destination_tag != expected_destination.tag {
+            return Err(Error::validation(format!(
+                "Payment has destination tag {destination_tag:?}, expected {:?}",
+                expected_destination.tag
+            )));
+        }
+
+        let flags = self.fields.get("Flags").and_then(Value::as_u64).unwrap_or(0);
+        if flags & TF_PARTIAL_PAYMENT != 0 {
+            return Err(Error::validation("Partial payments are not accepted"));
+        }
+
+        let amount = self
+            .fields
+            .get("Amount")
+            .ok_or_else(|| Error::validation("Payment has no Amount"))?;
+        let (token, value) = codec::amount_value(amount)?;
+        let (currency, issuer) = match &token {
+            None => ("XRP", None),
+            Some((currency, issuer)) => (currency.as_str(), Some(issuer.as_str())),
+        };
+
+        if currency != expected.currency || issuer != expected.issuer.as_deref() {
+            return Err(Error::validation(format!(
+                "Payment is in {currency} issued by {issuer:?}, expected {} issued by {:?}",
+                expected.currency, expected.issuer
+            )));
+        }
+
+        // Both in XRP for XRP payments, whose Amount is in drops
+        if value != expected.value {
+            return Err(Error::validation(format!(
+                "Payment amount is {value} {currency}, expected {}",
+                expected.value
+            )));
+        }
+
+        Ok(())
+    }
+}